@@ -0,0 +1,69 @@
+//! Builds a phase allowlist Merkle tree from a CSV file and prints the root to
+//! pass as `PhaseConfig::allowlist_root` and each wallet's proof for `buy_token`.
+//!
+//! The CSV has one `wallet,max_allocation` line per allowed wallet, with the
+//! allocation in token base units. Leave the allocation out for no cap.
+//!
+//!     cargo run --example allowlist -- allowlist.csv > allowlist.json
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use constants::utils::merkle::{allowlist_leaf, MerkleTree};
+
+fn parse_line(line: &str) -> Result<(Pubkey, u64), String> {
+    let mut columns = line.split(',').map(str::trim);
+    let wallet = columns.next().unwrap_or_default();
+    let wallet = Pubkey::from_str(wallet).map_err(|_| format!("invalid wallet `{wallet}`"))?;
+    let max_allocation = match columns.next() {
+        Some(cap) if !cap.is_empty() => cap.parse().map_err(|_| format!("invalid allocation `{cap}`"))?,
+        _ => u64::MAX,
+    };
+    Ok((wallet, max_allocation))
+}
+
+fn to_json(hash: &[u8; 32]) -> String {
+    let bytes: Vec<String> = hash.iter().map(u8::to_string).collect();
+    format!("[{}]", bytes.join(","))
+}
+
+fn main() {
+    let path = std::env::args().nth(1).expect("usage: allowlist <csv file>");
+    let csv = std::fs::read_to_string(&path).expect("failed to read the CSV file");
+
+    let mut entries = Vec::new();
+    for (number, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        // Skip blank lines, comments and a header row
+        if line.is_empty() || line.starts_with('#') || (number == 0 && line.starts_with("wallet")) {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(entry) => entries.push(entry),
+            Err(error) => panic!("line {}: {}", number + 1, error),
+        }
+    }
+
+    let leaves = entries.iter()
+        .map(|(wallet, max_allocation)| allowlist_leaf(wallet, *max_allocation))
+        .collect();
+    let tree = MerkleTree::new(leaves);
+
+    let proofs: Vec<String> = entries.iter()
+        .enumerate()
+        .map(|(i, (wallet, max_allocation))| {
+            let proof: Vec<String> = tree.proof(i).iter().map(to_json).collect();
+            format!(
+                "    \"{}\": {{ \"maxAllocation\": \"{}\", \"proof\": [{}] }}",
+                wallet, max_allocation, proof.join(", ")
+            )
+        })
+        .collect();
+
+    println!("{{");
+    println!("  \"root\": {},", to_json(&tree.root()));
+    println!("  \"proofs\": {{");
+    println!("{}", proofs.join(",\n"));
+    println!("  }}");
+    println!("}}");
+}
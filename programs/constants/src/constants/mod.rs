@@ -1,5 +1,6 @@
+#[allow(clippy::module_inception)]
 pub mod constants;
 pub mod presale_config;
 
 pub use constants::*;
-pub use presale_config::*;
\ No newline at end of file
+pub use presale_config::*;
@@ -44,4 +44,56 @@ pub enum PresaleError {
     PresalePaused,
     #[msg("Presale time window has expired")]
     PresaleTimeExpired,
+    #[msg("Phase has not started yet")]
+    PhaseNotStarted,
+    #[msg("Exceeds maximum tokens per address for this phase")]
+    ExceedsPhaseMaxAmount,
+    #[msg("No unsold tokens to release")]
+    NoUnsoldTokens,
+    #[msg("Invalid presale state transition")]
+    InvalidStateTransition,
+    #[msg("Presale has not been finalized")]
+    PresaleNotFinalized,
+    #[msg("Token claims have not started yet")]
+    ClaimNotStarted,
+    #[msg("No vested tokens to claim yet")]
+    NothingVested,
+    #[msg("Presale softcap has not been reached")]
+    SoftcapNotReached,
+    #[msg("A softcap requires the final phase to have an end time")]
+    MissingEndTime,
+    #[msg("Refunds are only available when the presale ends below its softcap")]
+    RefundNotAvailable,
+    #[msg("Nothing to refund")]
+    NothingToRefund,
+    #[msg("Price moved beyond the buyer's slippage tolerance")]
+    SlippageExceeded,
+    #[msg("Payment mint is not accepted by this presale or phase")]
+    PaymentMintNotAccepted,
+    #[msg("Invalid payment mint configuration")]
+    InvalidPaymentMints,
+    #[msg("Missing or invalid price feed account")]
+    InvalidPriceFeed,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Price feed confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Wallet is not on the phase allowlist")]
+    NotAllowlisted,
+    #[msg("Purchase exceeds the wallet's allowlist allocation")]
+    ExceedsAllowlistAllocation,
+    #[msg("This presale requires a KYC purchase voucher")]
+    MissingVoucher,
+    #[msg("Invalid purchase voucher")]
+    InvalidVoucher,
+    #[msg("Purchase voucher has expired")]
+    VoucherExpired,
+    #[msg("Purchase voucher nonce was already used")]
+    VoucherReplayed,
+    #[msg("Purchase exceeds the voucher amount")]
+    ExceedsVoucherAmount,
+    #[msg("Amount exceeds the tokens not owed to buyers")]
+    ExceedsSurplus,
+    #[msg("A softcap can only be set on presales paid in SOL alone")]
+    SoftcapWithPaymentMints,
 }
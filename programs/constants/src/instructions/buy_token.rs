@@ -2,21 +2,70 @@ use anchor_lang::{prelude::*, system_program};
 
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount};
-use solana_program::clock::Clock;
-
 use solana_program::account_info::AccountInfo;
 
 use crate::errors::PresaleError;
-use crate::state::{PresaleInfo, UserInfo, PhaseStatus};
+use crate::state::{OraclePrice, PresaleInfo, PresaleState, UserInfo};
+use crate::utils::ed25519::verify_preceding_ed25519_signature;
+use anchor_lang::solana_program::sysvar;
 use crate::constants::presale_config::DECIMALS_MULTIPLIER;
 
+// Proof that the beneficiary is on the allowlist of the phases being bought from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct AllowlistProof {
+    // Allocation committed to in the beneficiary's leaf, the most it may buy per allowlisted phase
+    pub max_allocation: u64,
+    // Sibling hashes from the leaf up to the root
+    pub proof: Vec<[u8; 32]>,
+}
+
+// Purchase approval signed off-chain by the presale's `kyc_signer`. The signature
+// is checked by an Ed25519 precompile instruction right before the purchase.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PurchaseVoucher {
+    // Wallet receiving the allocation (the purchase beneficiary)
+    pub buyer: Pubkey,
+    // Most tokens the purchase may buy, in base units
+    pub max_amount: u64,
+    // Unix timestamp after which the voucher is rejected
+    pub expiry: i64,
+    // Must be higher than the last nonce the buyer used
+    pub nonce: u64,
+}
+
+impl PurchaseVoucher {
+    pub const MESSAGE_LEN: usize = 32 + 32 + 8 + 8 + 8;
+
+    // Bytes the KYC signer signs: presale || buyer || max_amount || expiry || nonce,
+    // integers little endian
+    pub fn message(&self, presale: &Pubkey) -> Vec<u8> {
+        let mut message = Vec::with_capacity(Self::MESSAGE_LEN);
+        message.extend_from_slice(presale.as_ref());
+        message.extend_from_slice(self.buyer.as_ref());
+        message.extend_from_slice(&self.max_amount.to_le_bytes());
+        message.extend_from_slice(&self.expiry.to_le_bytes());
+        message.extend_from_slice(&self.nonce.to_le_bytes());
+        message
+    }
+
+    pub fn check(&self, beneficiary: &Pubkey, amount: u64, now: i64, last_nonce: u64) -> Result<()> {
+        require_keys_eq!(self.buyer, *beneficiary, PresaleError::InvalidVoucher);
+        require!(now <= self.expiry, PresaleError::VoucherExpired);
+        require!(self.nonce > last_nonce, PresaleError::VoucherReplayed);
+        require!(amount <= self.max_amount, PresaleError::ExceedsVoucherAmount);
+        Ok(())
+    }
+}
+
 #[derive(Accounts)]
+#[instruction(beneficiary: Pubkey)]
 pub struct BuyToken<'info> {
     #[account(
         mut,
         seeds = [
             b"presale",
-            presale_info.authority.as_ref()
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
         ],
         bump,
     )]
@@ -25,11 +74,11 @@ pub struct BuyToken<'info> {
     #[account(
         init_if_needed,
         payer = buyer,
-        space = 8 + std::mem::size_of::<UserInfo>(),
+        space = UserInfo::space(presale_info.phases.len()),
         seeds = [
-            b"user",
+            UserInfo::SEED_PREFIX,
             presale_info.key().as_ref(),
-            buyer.key().as_ref()
+            beneficiary.as_ref()
         ],
         bump,
     )]
@@ -38,11 +87,15 @@ pub struct BuyToken<'info> {
     /// CHECK: This is not dangerous
     #[account(
         mut,
-        seeds = [b"vault"],
-        bump
+        seeds = [
+            b"vault",
+            presale_info.key().as_ref()
+        ],
+        bump = presale_info.vault_bump
     )]
     pub presale_vault: AccountInfo<'info>,
 
+    // Pays for the purchase, the allocation belongs to `beneficiary`
     #[account(mut)]
     pub buyer: Signer<'info>,
 
@@ -52,155 +105,287 @@ pub struct BuyToken<'info> {
     #[account(mut)]
     pub presale_token_account: Account<'info, TokenAccount>,
 
+    /// CHECK: SOL/USD price account, only required for USD priced presales.
+    /// Checked against `presale_info.price_feed` and parsed in `load_sol_usd_price`
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar, only required for presales with a `kyc_signer`
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn buy_token(ctx: Context<BuyToken>, amount: u64) -> Result<()> {
-    let presale_info = &mut ctx.accounts.presale_info;
-    let user_info = &mut ctx.accounts.user_info;
+pub fn buy_token(
+    ctx: Context<BuyToken>,
+    beneficiary: Pubkey,
+    amount: u64,
+    max_payment: u64,
+    allowlist_proof: Option<AllowlistProof>,
+    voucher: Option<PurchaseVoucher>,
+) -> Result<()> {
+    process_purchase(
+        ctx.accounts,
+        beneficiary,
+        amount,
+        max_payment,
+        allowlist_proof.as_ref(),
+        voucher.as_ref(),
+    )?;
+    Ok(())
+}
+
+// Sells `amount` tokens to `beneficiary` starting at the active phase, spilling
+// into the following phases if it sells out, charging the buyer at most
+// `max_payment` lamports. Returns the lamports actually paid.
+pub(crate) fn process_purchase(
+    accounts: &mut BuyToken,
+    beneficiary: Pubkey,
+    amount: u64,
+    max_payment: u64,
+    allowlist: Option<&AllowlistProof>,
+    voucher: Option<&PurchaseVoucher>,
+) -> Result<u64> {
+    let now = Clock::get()?.unix_timestamp;
+    let sol_usd_price = load_sol_usd_price(&accounts.presale_info, accounts.price_feed.as_ref(), now)?;
+    process_priced_purchase(
+        accounts,
+        PurchaseOrder {
+            beneficiary,
+            amount,
+            max_payment,
+            mint_index: None,
+            sol_usd_price,
+            allowlist,
+            voucher,
+        },
+        now,
+    )
+}
+
+// Same as `process_purchase` for callers that already read the clock and the
+// SOL/USD price into `order`
+pub(crate) fn process_priced_purchase(
+    accounts: &mut BuyToken,
+    order: PurchaseOrder,
+    now: i64,
+) -> Result<u64> {
+    let beneficiary = order.beneficiary;
+    let voucher = load_voucher(&accounts.presale_info, order.voucher, accounts.instructions_sysvar.as_ref())?;
+    let payment_amount = execute_purchase(
+        &mut accounts.presale_info,
+        &mut accounts.user_info,
+        PurchaseOrder { voucher, ..order },
+        now,
+    )?;
+
+    // Transfer SOL from buyer to presale vault
+    system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: accounts.buyer.to_account_info(),
+                to: accounts.presale_vault.to_account_info(),
+            }
+        ),
+        payment_amount
+    )?;
+
+    msg!("Amount paid: {} lamports", payment_amount);
+    if beneficiary != accounts.buyer.key() {
+        msg!("Paid by {} on behalf of {}", accounts.buyer.key(), beneficiary);
+    }
+
+    Ok(payment_amount)
+}
+
+// Reads the SOL/USD price of a USD priced presale from its configured feed,
+// `None` for presales priced in lamports
+pub(crate) fn load_sol_usd_price(
+    presale_info: &PresaleInfo,
+    price_feed: Option<&UncheckedAccount>,
+    now: i64,
+) -> Result<Option<OraclePrice>> {
+    let Some(config) = presale_info.price_feed else {
+        return Ok(None);
+    };
+    let feed = price_feed.ok_or(PresaleError::InvalidPriceFeed)?;
+    require_keys_eq!(feed.key(), config.feed, PresaleError::InvalidPriceFeed);
+
+    let price = OraclePrice::parse(&feed.try_borrow_data()?)?;
+    price.validate(&config, now)?;
+    msg!("SOL/USD price: {} x 10^{} (conf {})", price.price, price.expo, price.conf);
+    Ok(Some(price))
+}
+
+// Checks the voucher of a presale with a `kyc_signer` was signed by it in the
+// preceding Ed25519 instruction, `None` for presales without KYC
+pub(crate) fn load_voucher<'a>(
+    presale_info: &Account<PresaleInfo>,
+    voucher: Option<&'a PurchaseVoucher>,
+    instructions_sysvar: Option<&UncheckedAccount>,
+) -> Result<Option<&'a PurchaseVoucher>> {
+    let Some(kyc_signer) = presale_info.kyc_signer else {
+        return Ok(None);
+    };
+    let voucher = voucher.ok_or(PresaleError::MissingVoucher)?;
+    let instructions_sysvar = instructions_sysvar.ok_or(PresaleError::MissingVoucher)?;
+    verify_preceding_ed25519_signature(
+        instructions_sysvar,
+        &kyc_signer,
+        &voucher.message(&presale_info.key()),
+    )?;
+    Ok(Some(voucher))
+}
+
+// A purchase of `amount` tokens for `beneficiary`, shared by every buy instruction
+pub(crate) struct PurchaseOrder<'a> {
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    // Most the buyer accepts to pay, in lamports or payment mint base units
+    pub max_payment: u64,
+    // Payment mint paid in, `None` for lamports
+    pub mint_index: Option<usize>,
+    // Converts the cost of USD priced phases to lamports
+    pub sol_usd_price: Option<OraclePrice>,
+    pub allowlist: Option<&'a AllowlistProof>,
+    // KYC voucher, its signature is checked by `load_voucher` before execution
+    pub voucher: Option<&'a PurchaseVoucher>,
+}
+
+// Validates and records a purchase. Returns the amount owed, which the caller
+// must transfer into the presale.
+pub(crate) fn execute_purchase(
+    presale_info: &mut PresaleInfo,
+    user_info: &mut UserInfo,
+    order: PurchaseOrder,
+    now: i64,
+) -> Result<u64> {
+    let PurchaseOrder { beneficiary, amount, max_payment, mint_index, sol_usd_price, allowlist, voucher } = order;
+
+    // The default key marks a new user_info and can never sign a claim
+    require_keys_neq!(beneficiary, Pubkey::default(), PresaleError::InvalidAuthority);
 
     // Initialize user_info if it's new
     if user_info.wallet == Pubkey::default() {
-        user_info.wallet = ctx.accounts.buyer.key();
-        user_info.tokens_bought = 0;
-        user_info.phase_purchases = [0; 6];
-        user_info.last_purchase_time = 0;
-        user_info.phase_claims = [false; 6];  // Initialize all phases as unclaimed
-        user_info.total_paid = 0;
+        user_info.initialize(beneficiary, presale_info.phases.len(), presale_info.payment_mints.len());
     }
 
     // Check if presale is in a valid state for participation
-    require!(presale_info.can_participate(), PresaleError::PresaleNotActive);
+    match presale_info.state {
+        PresaleState::Live => {}
+        PresaleState::Draft => return err!(PresaleError::PresaleNotActive),
+        PresaleState::Paused => return err!(PresaleError::PresalePaused),
+        PresaleState::Finalized | PresaleState::Cancelled => return err!(PresaleError::PresaleEnded),
+    }
 
     // Basic validations
-    require!(presale_info.is_initialized, PresaleError::PresaleNotInitialized);
-    require!(presale_info.is_active, PresaleError::PresaleNotActive);
-    require!(!presale_info.is_ended, PresaleError::PresaleEnded);
+    require!(amount > 0, PresaleError::InvalidAmount);
+
+    // Presales with a KYC signer only sell against a fresh voucher for this buyer
+    if presale_info.kyc_signer.is_some() {
+        let voucher = voucher.ok_or(PresaleError::MissingVoucher)?;
+        voucher.check(&beneficiary, amount, now, user_info.last_voucher_nonce)?;
+        user_info.last_voucher_nonce = voucher.nonce;
+    }
 
-    // Get current phase info
-    let phase_number = presale_info.current_phase;
-    let phase_index = (phase_number - 1) as usize;
+    // Close any phases whose end time has passed before pricing the purchase
+    presale_info.close_expired_phases(now)?;
+
+    let starting_phase = presale_info.current_phase;
     let max_token_amount = presale_info.max_token_amount_per_address;
-    
-    // Validate user's phase-specific purchase would not exceed max tokens per address
-    let current_phase_amount = user_info.phase_purchases[phase_index];
-    let new_phase_total = current_phase_amount.checked_add(amount)
+
+    // Validate user's lifetime purchases would not exceed max tokens per address
+    let new_wallet_total = user_info.tokens_bought.checked_add(amount)
         .ok_or(PresaleError::Overflow)?;
-    
     require!(
-        new_phase_total <= max_token_amount,
+        presale_info.is_within_wallet_cap(new_wallet_total),
         PresaleError::ExceedsMaxAmount
     );
-    msg!("Phase {} - Current phase total: {}, New phase total: {}, Max allowed per phase: {}", 
-        phase_number,
-        current_phase_amount / DECIMALS_MULTIPLIER,
-        new_phase_total / DECIMALS_MULTIPLIER,
-        max_token_amount / DECIMALS_MULTIPLIER
-    );
-    
-    // First, validate phase and get necessary information
-    let (phase_price, is_phase_complete, next_phase_price) = {
-        let phase = &presale_info.phases[(phase_number - 1) as usize];
-        require!(phase.status == PhaseStatus::Active, PresaleError::PhaseNotActive);
-        require!(amount <= phase.tokens_available, PresaleError::InsufficientTokens);
-        
-        let tokens_sold_after = phase.tokens_sold.checked_add(amount)
-            .ok_or(PresaleError::Overflow)?;
-        let tokens_available_after = phase.tokens_available.checked_sub(amount)
-            .ok_or(PresaleError::Overflow)?;
-        
-        let will_complete = tokens_available_after == 0;
-        let next_price = if will_complete && phase_number < PresaleInfo::TOTAL_PHASES as u8 {
-            Some(presale_info.phases[phase_number as usize].price)
-        } else {
-            None
-        };
-        
-        (phase.price, will_complete, next_price)
-    };
 
-    // Calculate payment before any mutations
-    let actual_tokens = amount.checked_div(DECIMALS_MULTIPLIER)
-        .ok_or(PresaleError::Overflow)?;
-    let payment_amount = phase_price.checked_mul(actual_tokens)
+    // Only sell tokens the authority has actually deposited
+    require!(presale_info.is_deposit_sufficient(amount), PresaleError::InsufficientDeposit);
+
+    // Split the purchase across phases and price each part before any mutations
+    let mut fills = presale_info.plan_purchase_in(amount, now, mint_index)?;
+    if mint_index.is_none() && presale_info.is_usd_priced() {
+        let price = sol_usd_price.ok_or(PresaleError::InvalidPriceFeed)?;
+        price.convert_fills(&mut fills)?;
+    }
+    let payment_amount = fills.iter()
+        .try_fold(0u64, |acc, fill| acc.checked_add(fill.cost))
         .ok_or(PresaleError::Overflow)?;
+    require!(payment_amount > 0, PresaleError::InvalidPrice);
+    require!(payment_amount <= max_payment, PresaleError::SlippageExceeded);
 
-    // Now perform all mutations
-    {
-        let phase = &mut presale_info.phases[(phase_number - 1) as usize];
-        phase.tokens_sold = phase.tokens_sold.checked_add(amount)
+    // Validate user's phase-specific purchases would not exceed the phase caps, if any
+    for fill in &fills {
+        let phase_index = (fill.phase_number - 1) as usize;
+        let new_phase_total = user_info.phase_purchases[phase_index].checked_add(fill.amount)
             .ok_or(PresaleError::Overflow)?;
-        phase.tokens_available = phase.tokens_available.checked_sub(amount)
-            .ok_or(PresaleError::Overflow)?;
-        
-        if is_phase_complete {
-            phase.status = PhaseStatus::Ended;
-        }
-    }
+        let phase = &presale_info.phases[phase_index];
+        require!(
+            phase.is_within_wallet_cap(new_phase_total),
+            PresaleError::ExceedsPhaseMaxAmount
+        );
 
-    // Handle phase transition and global state updates
-    if is_phase_complete && phase_number < PresaleInfo::TOTAL_PHASES as u8 {
-        presale_info.current_phase += 1;
-        presale_info.phases[phase_number as usize].status = PhaseStatus::Active;
-        
-        msg!("Phase {} completed! Moving to Phase {}", phase_number, phase_number + 1);
-        if let Some(next_price) = next_phase_price {
-            msg!("New phase price: {} lamports", next_price);
+        // Allowlisted phases only sell to wallets in their Merkle tree, up to the leaf allocation
+        if phase.allowlist_root.is_some() {
+            let allowlist = allowlist.ok_or(PresaleError::NotAllowlisted)?;
+            require!(
+                phase.is_allowlisted(&beneficiary, allowlist.max_allocation, &allowlist.proof),
+                PresaleError::NotAllowlisted
+            );
+            require!(
+                new_phase_total <= allowlist.max_allocation,
+                PresaleError::ExceedsAllowlistAllocation
+            );
         }
     }
+    msg!("Phase {} - New wallet total: {}, Max allowed per address: {}",
+        starting_phase,
+        new_wallet_total / DECIMALS_MULTIPLIER,
+        max_token_amount / DECIMALS_MULTIPLIER
+    );
 
-    // Update global presale state
-    presale_info.total_tokens_sold = presale_info.total_tokens_sold
-        .checked_add(amount)
-        .ok_or(PresaleError::Overflow)?;
-    presale_info.remaining_tokens = presale_info.remaining_tokens
-        .checked_sub(amount)
-        .ok_or(PresaleError::Overflow)?;
-
-    // Transfer SOL from buyer to presale vault
-    system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.buyer.to_account_info(),
-                to: ctx.accounts.presale_vault.to_account_info(),
-            }
-        ),
-        payment_amount
-    )?;
+    // Now perform all mutations, recording each phase touched separately
+    for fill in &fills {
+        presale_info.apply_fill(fill)?;
+        let lamports = if mint_index.is_none() { fill.cost } else { 0 };
+        user_info.record_purchase(fill.phase_number, fill.amount, lamports)?;
+        msg!("Phase {}: {} tokens for {}",
+            fill.phase_number,
+            fill.amount / DECIMALS_MULTIPLIER,
+            fill.cost
+        );
+    }
 
-    // Update user info
-    user_info.phase_purchases[phase_index] = new_phase_total;
-    // Calculate total tokens bought as sum of all phases
-    user_info.tokens_bought = user_info.phase_purchases.iter()
-        .fold(0, |acc, &phase_amount| acc.checked_add(phase_amount)
-            .unwrap_or(acc));
-    user_info.last_purchase_time = Clock::get()?.unix_timestamp;
-    user_info.total_paid = user_info.total_paid
-        .checked_add(payment_amount)
-        .ok_or(PresaleError::Overflow)?;
+    match mint_index {
+        None => presale_info.record_payment(payment_amount)?,
+        Some(index) => {
+            presale_info.record_token_payment(index, payment_amount)?;
+            user_info.record_token_payment(index, payment_amount)?;
+        }
+    }
 
-    // Check if presale is complete and update final states
+    // Sold out presales stay Live until the authority finalizes them
     if presale_info.remaining_tokens == 0 {
-        presale_info.is_ended = true;
-        presale_info.is_active = false;
         msg!("Presale completed! All tokens sold.");
     }
 
     // Calculate final phase statistics
     let final_percentage = {
-        let phase = &presale_info.phases[(phase_number - 1) as usize];
-        (phase.tokens_sold * 100) / phase.amount
+        let phase = &presale_info.phases[(presale_info.current_phase - 1) as usize];
+        (phase.tokens_sold as u128 * 100).checked_div(phase.amount as u128).unwrap_or(0) as u64
     };
 
     msg!("Purchase successful!");
     msg!("Tokens purchased: {}", amount / DECIMALS_MULTIPLIER);
-    msg!("Amount paid: {} lamports", payment_amount);
-    msg!("Current phase: {} ({}% sold)", phase_number, final_percentage);
-    msg!("User's total tokens purchased: {}", new_phase_total / DECIMALS_MULTIPLIER);
+    msg!("Current phase: {} ({}% sold)", presale_info.current_phase, final_percentage);
+    msg!("User's total tokens purchased: {}", user_info.tokens_bought / DECIMALS_MULTIPLIER);
 
-    Ok(())
-}
\ No newline at end of file
+    Ok(payment_amount)
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PresaleError;
+use crate::instructions::buy_token::{load_sol_usd_price, process_priced_purchase, AllowlistProof, BuyToken, PurchaseOrder, PurchaseVoucher};
+use crate::constants::presale_config::DECIMALS_MULTIPLIER;
+
+// Buy as many tokens as `lamports_in` affords at the active phase price,
+// continuing into the next phases at their prices if it sells out. Reverts
+// if that is fewer than `min_tokens_out`, e.g. because the phase completed
+// and the price changed before this transaction landed.
+pub fn buy_with_sol(
+    ctx: Context<BuyToken>,
+    beneficiary: Pubkey,
+    lamports_in: u64,
+    min_tokens_out: u64,
+    allowlist_proof: Option<AllowlistProof>,
+    voucher: Option<PurchaseVoucher>,
+) -> Result<()> {
+    require!(lamports_in > 0, PresaleError::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let sol_usd_price = load_sol_usd_price(&ctx.accounts.presale_info, ctx.accounts.price_feed.as_ref(), now)?;
+    let presale_info = &mut ctx.accounts.presale_info;
+    presale_info.close_expired_phases(now)?;
+
+    // USD priced phases are quoted against the USD value of the lamports, rounded down
+    let budget = match sol_usd_price {
+        Some(price) => price.lamports_to_usd(lamports_in).ok_or(PresaleError::Overflow)?,
+        None => lamports_in,
+    };
+    let tokens_out = presale_info.quote_tokens_for_lamports(budget, now)?;
+
+    msg!(
+        "Quoted {} tokens for {} lamports (minimum accepted: {})",
+        tokens_out / DECIMALS_MULTIPLIER,
+        lamports_in,
+        min_tokens_out / DECIMALS_MULTIPLIER
+    );
+    require!(tokens_out >= min_tokens_out, PresaleError::SlippageExceeded);
+
+    // Reuses the clock and SOL/USD price the quote was made with
+    let payment_amount = process_priced_purchase(
+        ctx.accounts,
+        PurchaseOrder {
+            beneficiary,
+            amount: tokens_out,
+            max_payment: lamports_in,
+            mint_index: None,
+            sol_usd_price,
+            allowlist: allowlist_proof.as_ref(),
+            voucher: voucher.as_ref(),
+        },
+        now,
+    )?;
+    msg!("Unspent lamports kept by buyer: {}", lamports_in - payment_amount);
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use {
+    anchor_lang::prelude::*,
+    anchor_spl::token,
+};
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+
+use crate::errors::PresaleError;
+use crate::instructions::buy_token::{execute_purchase, load_voucher, AllowlistProof, PurchaseOrder, PurchaseVoucher};
+use anchor_lang::solana_program::sysvar;
+use crate::state::{PresaleInfo, UserInfo};
+
+#[derive(Accounts)]
+#[instruction(beneficiary: Pubkey)]
+pub struct BuyWithToken<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub presale_info: Box<Account<'info, PresaleInfo>>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = UserInfo::space(presale_info.phases.len()),
+        seeds = [
+            UserInfo::SEED_PREFIX,
+            presale_info.key().as_ref(),
+            beneficiary.as_ref()
+        ],
+        bump,
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(
+        constraint = presale_info.payment_mint_index(&payment_mint.key()).is_some()
+            @ PresaleError::PaymentMintNotAccepted
+    )]
+    pub payment_mint: Box<Account<'info, token::Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer,
+    )]
+    pub buyer_payment_account: Box<Account<'info, token::TokenAccount>>,
+
+    // Presale-owned vault receiving payments in this mint
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = presale_info,
+    )]
+    pub presale_payment_account: Box<Account<'info, token::TokenAccount>>,
+
+    // Pays for the purchase, the allocation belongs to `beneficiary`
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: instructions sysvar, only required for presales with a `kyc_signer`
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Same as `buy_token`, paid in one of the presale's SPL payment mints at the
+// phase prices set for that mint. `max_payment` is in the mint's base units.
+pub fn buy_with_token(
+    ctx: Context<BuyWithToken>,
+    beneficiary: Pubkey,
+    amount: u64,
+    max_payment: u64,
+    allowlist_proof: Option<AllowlistProof>,
+    voucher: Option<PurchaseVoucher>,
+) -> Result<()> {
+    let mint_index = ctx.accounts.presale_info
+        .payment_mint_index(&ctx.accounts.payment_mint.key())
+        .ok_or(PresaleError::PaymentMintNotAccepted)?;
+    let voucher = load_voucher(
+        &ctx.accounts.presale_info,
+        voucher.as_ref(),
+        ctx.accounts.instructions_sysvar.as_ref(),
+    )?;
+
+    let payment_amount = execute_purchase(
+        &mut ctx.accounts.presale_info,
+        &mut ctx.accounts.user_info,
+        PurchaseOrder {
+            beneficiary,
+            amount,
+            max_payment,
+            mint_index: Some(mint_index),
+            sol_usd_price: None,
+            allowlist: allowlist_proof.as_ref(),
+            voucher,
+        },
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    // Transfer the payment from the buyer to the presale's vault for this mint
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.buyer_payment_account.to_account_info(),
+                to: ctx.accounts.presale_payment_account.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+            },
+        ),
+        payment_amount,
+    )?;
+
+    msg!("Amount paid: {} of mint {}", payment_amount, ctx.accounts.payment_mint.key());
+    if beneficiary != ctx.accounts.buyer.key() {
+        msg!("Paid by {} on behalf of {}", ctx.accounts.buyer.key(), beneficiary);
+    }
+
+    Ok(())
+}
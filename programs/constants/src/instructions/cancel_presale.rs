@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::{PresaleInfo, PresaleState};
+use crate::errors::PresaleError;
+
+#[derive(Accounts)]
+pub struct CancelPresale<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        constraint = authority.key() == presale_info.authority @ PresaleError::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn cancel_presale(ctx: Context<CancelPresale>) -> Result<()> {
+    let presale_info = &mut ctx.accounts.presale_info;
+
+    // Any presale that has not been finalized can be called off
+    presale_info.transition_to(PresaleState::Cancelled)?;
+    msg!("Presale has been cancelled by admin, buyers can now refund");
+
+    Ok(())
+}
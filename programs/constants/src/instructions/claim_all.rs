@@ -0,0 +1,66 @@
+use {
+    anchor_lang::prelude::*,
+    anchor_spl::token,
+};
+
+use crate::errors::PresaleError;
+use crate::instructions::ClaimToken;
+use crate::state::PresaleState;
+
+// Claims everything vested across all phases the buyer participated in,
+// using a single token transfer. Takes the same accounts as `claim_token`.
+pub fn claim_all(ctx: Context<ClaimToken>) -> Result<()> {
+    let presale_info = &mut ctx.accounts.presale_info;
+    let user_info = &mut ctx.accounts.user_info;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    // Tokens are only released once the presale is finalized and the TGE has passed
+    require!(presale_info.state == PresaleState::Finalized, PresaleError::PresaleNotFinalized);
+    require!(presale_info.is_claim_open(current_time), PresaleError::ClaimNotStarted);
+    require!(user_info.tokens_bought > 0, PresaleError::InvalidPhase);
+
+    // Vested and unclaimed amount of every eligible phase
+    let (claims, claim_amount) = presale_info.plan_claim_all(user_info, current_time)?;
+
+    msg!("Claiming {} tokens from {} phases", claim_amount, claims.len());
+
+    // Transfer tokens
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.presale_associated_token_account.to_account_info(),
+                to: ctx.accounts.token_account.to_account_info(),
+                authority: presale_info.to_account_info(),
+            },
+            &[&[
+                b"presale",
+                presale_info.authority.as_ref(),
+                &presale_info.presale_id.to_le_bytes(),
+                &[ctx.bumps.presale_info],
+            ][..]],
+        ),
+        claim_amount,
+    )?;
+
+    // Update user info - record each phase's claimed amount
+    for (phase_number, amount) in claims.iter() {
+        user_info.record_claim(*phase_number, *amount)?;
+        msg!("Claimed {} tokens from phase {}", amount, phase_number);
+    }
+    user_info.last_purchase_time = current_time;
+    presale_info.record_claimed(claim_amount)?;
+
+    let still_locked: u64 = (0..user_info.phase_purchases.len())
+        .map(|i| {
+            user_info.phase_purchases[i]
+                .saturating_sub(presale_info.vested_amount(user_info, i, current_time))
+        })
+        .sum();
+
+    msg!("Successfully claimed {} tokens", claim_amount);
+    msg!("Delivered to: {}", ctx.accounts.destination_owner.key());
+    msg!("Tokens still vesting: {}", still_locked);
+
+    Ok(())
+}
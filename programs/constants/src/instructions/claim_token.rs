@@ -4,7 +4,7 @@ use {
 };
 
 use crate::errors::PresaleError;
-use crate::state::{PresaleInfo, UserInfo, PhaseStatus};
+use crate::state::{PresaleInfo, PresaleState, UserInfo, PhaseStatus};
 use anchor_spl::token::Token;
 use anchor_spl::associated_token::AssociatedToken;
 
@@ -20,10 +20,14 @@ pub struct ClaimToken<'info> {
         init_if_needed,
         payer = buyer,
         associated_token::mint = token_mint,
-        associated_token::authority = buyer,
+        associated_token::authority = destination_owner,
     )]
     pub token_account: Box<Account<'info, token::TokenAccount>>,
 
+    /// CHECK: any wallet the buyer wants the tokens delivered to, only used
+    /// as the owner of `token_account`
+    pub destination_owner: UncheckedAccount<'info>,
+
     #[account(
         mut,
         associated_token::mint = token_mint,
@@ -34,11 +38,12 @@ pub struct ClaimToken<'info> {
     #[account(
         mut,
         seeds = [
-            b"user",
+            UserInfo::SEED_PREFIX,
             presale_info.key().as_ref(),
             buyer.key().as_ref()
         ],        
-        bump
+        bump,
+        constraint = user_info.wallet == buyer.key() @ PresaleError::InvalidAuthority
     )]
     pub user_info: Box<Account<'info, UserInfo>>,
 
@@ -46,7 +51,8 @@ pub struct ClaimToken<'info> {
         mut,
         seeds = [
             b"presale",
-            presale_info.authority.as_ref()
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
         ],
         bump
     )]
@@ -62,13 +68,13 @@ pub struct ClaimToken<'info> {
 }
 
 pub fn claim_token(ctx: Context<ClaimToken>, phase_to_claim: u8) -> Result<()> {
-    let presale_info = &ctx.accounts.presale_info;
+    let presale_info = &mut ctx.accounts.presale_info;
     let user_info = &mut ctx.accounts.user_info;
     let current_time = Clock::get()?.unix_timestamp;
 
     // Validate phase number
     require!(
-        phase_to_claim > 0 && phase_to_claim <= PresaleInfo::TOTAL_PHASES as u8,
+        phase_to_claim > 0 && phase_to_claim <= presale_info.phase_count(),
         PresaleError::InvalidPhase
     );
 
@@ -85,10 +91,21 @@ pub fn claim_token(ctx: Context<ClaimToken>, phase_to_claim: u8) -> Result<()> {
         PresaleError::PhaseNotActive
     );
 
-    // Check if tokens for this phase were already claimed
-    require!(!user_info.phase_claims[phase_index], PresaleError::UserAlreadyClaimed);
+    // Tokens are only released once the presale is finalized and the TGE has passed
+    require!(presale_info.state == PresaleState::Finalized, PresaleError::PresaleNotFinalized);
+    require!(presale_info.is_claim_open(current_time), PresaleError::ClaimNotStarted);
+
+    // Check if tokens for this phase were already fully claimed
+    require!(
+        user_info.phase_claimed[phase_index] < phase_amount,
+        PresaleError::UserAlreadyClaimed
+    );
+
+    // Only what has vested so far and was not claimed before
+    let claim_amount = presale_info.claimable_amount(user_info, phase_index, current_time);
+    require!(claim_amount > 0, PresaleError::NothingVested);
 
-    msg!("Claiming {} tokens from phase {}", phase_amount, phase_to_claim);
+    msg!("Claiming {} of {} tokens from phase {}", claim_amount, phase_amount, phase_to_claim);
     
     // Transfer tokens
     token::transfer(
@@ -97,34 +114,37 @@ pub fn claim_token(ctx: Context<ClaimToken>, phase_to_claim: u8) -> Result<()> {
             token::Transfer {
                 from: ctx.accounts.presale_associated_token_account.to_account_info(),
                 to: ctx.accounts.token_account.to_account_info(),
-                authority: ctx.accounts.presale_info.to_account_info(),
+                authority: presale_info.to_account_info(),
             },
             &[&[
                 b"presale",
-                ctx.accounts.presale_info.authority.as_ref(),
+                presale_info.authority.as_ref(),
+                &presale_info.presale_id.to_le_bytes(),
                 &[ctx.bumps.presale_info],
             ][..]],
         ),
-        phase_amount,
+        claim_amount,
     )?;
 
-    // Update user info - only mark this phase as claimed
-    user_info.phase_claims[phase_index] = true;
+    // Update user info - record how much of this phase has been claimed
+    user_info.record_claim(phase_to_claim, claim_amount)?;
+    presale_info.record_claimed(claim_amount)?;
     user_info.last_purchase_time = current_time;
 
-    // Calculate remaining claimable tokens from ended or active phases
-    let remaining_claimable = user_info.phase_purchases.iter()
-        .enumerate()
-        .filter(|(i, &amount)| {
-            let phase_status = presale_info.phases[*i].status;
-            !user_info.phase_claims[*i] && amount > 0 && 
-            (phase_status == PhaseStatus::Ended || phase_status == PhaseStatus::Active)
-        })
-        .map(|(_, &amount)| amount)
-        .sum::<u64>();
-
-    msg!("Successfully claimed {} tokens from phase {}", phase_amount, phase_to_claim);
+    // Calculate remaining claimable and still locked tokens across all phases
+    let (remaining_claimable, still_locked) = (0..user_info.phase_purchases.len())
+        .fold((0u64, 0u64), |(claimable, locked), i| {
+            let vested = presale_info.vested_amount(user_info, i, current_time);
+            (
+                claimable + vested.saturating_sub(user_info.phase_claimed[i]),
+                locked + user_info.phase_purchases[i].saturating_sub(vested),
+            )
+        });
+
+    msg!("Successfully claimed {} tokens from phase {}", claim_amount, phase_to_claim);
+    msg!("Delivered to: {}", ctx.accounts.destination_owner.key());
     msg!("Remaining claimable tokens: {}", remaining_claimable);
+    msg!("Tokens still vesting: {}", still_locked);
 
     Ok(())
 }
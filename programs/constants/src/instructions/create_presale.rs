@@ -1,22 +1,59 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program};
 
 use crate::errors::PresaleError;
-use crate::state::{PresaleInfo, Phase, PhaseStatus};
-use crate::constants::presale_config::*;
+use crate::state::{PresaleInfo, PresaleState, Phase, PhaseConfig, PhaseStatus, PriceFeedConfig, UnsoldTokenPolicy};
+use crate::constants::presale_config::DECIMALS_MULTIPLIER;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CreatePresaleParams {
+    // Identifier of this presale among the authority's presales
+    pub presale_id: u64,
+    // Mint address of the presale token
+    pub token_mint_address: Pubkey,
+    // Maximum tokens an address can purchase across all phases
+    pub max_token_amount_per_address: u64,
+    // Display end time (Unix timestamp) - for showcase only
+    pub display_end_time: i64,
+    // Phase schedule, one entry per phase
+    pub phase_configs: Vec<PhaseConfig>,
+    // What happens to tokens a phase leaves unsold when it closes by time
+    pub unsold_token_policy: UnsoldTokenPolicy,
+    // Minimum lamports to raise, otherwise buyers can refund (0 = none)
+    pub softcap_lamports: u64,
+    // SPL mints accepted as payment besides SOL, priced per phase in `PhaseConfig::token_prices`
+    pub payment_mints: Vec<Pubkey>,
+    // SOL/USD oracle to price phases in USD micro-units, `None` prices them in lamports
+    pub price_feed: Option<PriceFeedConfig>,
+    // Key of the compliance backend signing purchase vouchers, `None` = no KYC
+    pub kyc_signer: Option<Pubkey>,
+}
 
 #[derive(Accounts)]
+#[instruction(params: CreatePresaleParams)]
 pub struct CreatePresale<'info> {
     #[account(
         init,
         seeds = [
             b"presale",
-            authority.key().as_ref()
+            authority.key().as_ref(),
+            &params.presale_id.to_le_bytes()
         ],
         bump,
         payer = authority,
-        space = 8 + std::mem::size_of::<PresaleInfo>(),
+        space = PresaleInfo::space(params.phase_configs.len()),
     )]
     pub presale_info: Box<Account<'info, PresaleInfo>>,
+
+    /// CHECK: SOL vault PDA of this presale, funded to rent exemption here
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            presale_info.key().as_ref()
+        ],
+        bump
+    )]
+    pub presale_vault: AccountInfo<'info>,
     // Box is a memory management tool which allows you to allocate account memory on the heap instead. It gives you a pointer to the heap memory where your account data is stored. This helps save on stack space
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -25,112 +62,131 @@ pub struct CreatePresale<'info> {
 }
 
 // Edit the details for a presale
-pub fn create_presale(
-    ctx: Context<CreatePresale>,
-    token_mint_address: Pubkey,
-    max_token_amount_per_address: u64,
-    display_end_time: i64,
-) -> Result<()> {
+pub fn create_presale(ctx: Context<CreatePresale>, params: CreatePresaleParams) -> Result<()> {
+    let CreatePresaleParams {
+        presale_id,
+        token_mint_address,
+        max_token_amount_per_address,
+        display_end_time,
+        phase_configs,
+        unsold_token_policy,
+        softcap_lamports,
+        payment_mints,
+        price_feed,
+        kyc_signer,
+    } = params;
+
     require!(max_token_amount_per_address > 0, PresaleError::InvalidAmount);
+    if let Some(config) = &price_feed {
+        require!(config.is_valid(), PresaleError::InvalidPriceFeed);
+    }
+
+    require!(
+        PresaleInfo::is_valid_phase_count(phase_configs.len()),
+        PresaleError::InvalidPhaseAllocation
+    );
 
     let presale_info = &mut ctx.accounts.presale_info;
     let authority = &ctx.accounts.authority;
 
-    // Initialize phases with predefined values from constants
-    let phases = [
-        Phase {
-            phase_number: 1,
-            amount: PHASE_1_ALLOCATION,
-            price: PHASE_1_PRICE,
-            percentage: PHASE_1_PERCENTAGE,
-            tokens_sold: 0,
-            tokens_available: PHASE_1_ALLOCATION,
-            status: PhaseStatus::Active,
-            softcap: PHASE_1_MIN_PURCHASE,
-            hardcap: PHASE_1_MAX_PURCHASE,
-        },
-        Phase {
-            phase_number: 2,
-            amount: PHASE_2_ALLOCATION,
-            price: PHASE_2_PRICE,
-            percentage: PHASE_2_PERCENTAGE,
-            tokens_sold: 0,
-            tokens_available: PHASE_2_ALLOCATION,
-            status: PhaseStatus::Upcoming,
-            softcap: PHASE_2_MIN_PURCHASE,
-            hardcap: PHASE_2_MAX_PURCHASE,
-        },
-        Phase {
-            phase_number: 3,
-            amount: PHASE_3_ALLOCATION,
-            price: PHASE_3_PRICE,
-            percentage: PHASE_3_PERCENTAGE,
-            tokens_sold: 0,
-            tokens_available: PHASE_3_ALLOCATION,
-            status: PhaseStatus::Upcoming,
-            softcap: PHASE_3_MIN_PURCHASE,
-            hardcap: PHASE_3_MAX_PURCHASE,
-        },
-        Phase {
-            phase_number: 4,
-            amount: PHASE_4_ALLOCATION,
-            price: PHASE_4_PRICE,
-            percentage: PHASE_4_PERCENTAGE,
-            tokens_sold: 0,
-            tokens_available: PHASE_4_ALLOCATION,
-            status: PhaseStatus::Upcoming,
-            softcap: PHASE_4_MIN_PURCHASE,
-            hardcap: PHASE_4_MAX_PURCHASE,
-        },
-        Phase {
-            phase_number: 5,
-            amount: PHASE_5_ALLOCATION,
-            price: PHASE_5_PRICE,
-            percentage: PHASE_5_PERCENTAGE,
-            tokens_sold: 0,
-            tokens_available: PHASE_5_ALLOCATION,
-            status: PhaseStatus::Upcoming,
-            softcap: PHASE_5_MIN_PURCHASE,
-            hardcap: PHASE_5_MAX_PURCHASE,
-        },
-        Phase {
-            phase_number: 6,
-            amount: PHASE_6_ALLOCATION,
-            price: PHASE_6_PRICE,
-            percentage: PHASE_6_PERCENTAGE,
+    let total_token_supply = phase_configs.iter()
+        .try_fold(0u64, |acc, config| acc.checked_add(config.amount))
+        .ok_or(PresaleError::Overflow)?;
+    require!(total_token_supply > 0, PresaleError::InvalidPhaseAllocation);
+
+    // Initialize phases from the supplied configuration, first phase starts active
+    let mut phases = Vec::with_capacity(phase_configs.len());
+    for (i, config) in phase_configs.iter().enumerate() {
+        let percentage = (config.amount as u128 * 100 / total_token_supply as u128) as u8;
+        phases.push(Phase {
+            phase_number: (i + 1) as u8,
+            amount: config.amount,
+            price: config.price,
+            percentage,
             tokens_sold: 0,
-            tokens_available: PHASE_6_ALLOCATION,
-            status: PhaseStatus::Upcoming,
-            softcap: PHASE_6_MIN_PURCHASE,
-            hardcap: PHASE_6_MAX_PURCHASE,
-        },
-    ];
+            tokens_available: config.amount,
+            status: if i == 0 { PhaseStatus::Active } else { PhaseStatus::Upcoming },
+            softcap: config.min_purchase,
+            hardcap: config.max_purchase,
+            max_per_wallet: config.max_per_wallet,
+            start_time: config.start_time,
+            end_time: config.end_time,
+            vesting: config.vesting,
+            token_prices: config.token_prices.clone(),
+            allowlist_root: config.allowlist_root,
+        });
+    }
 
     // Initialize presale info
+    presale_info.presale_id = presale_id;
     presale_info.token_mint_address = token_mint_address;
-    presale_info.total_token_supply = TOTAL_SUPPLY;
-    presale_info.remaining_tokens = TOTAL_SUPPLY;
+    presale_info.total_token_supply = total_token_supply;
+    presale_info.remaining_tokens = total_token_supply;
     presale_info.current_phase = 1;
     presale_info.phases = phases;
     presale_info.total_tokens_sold = 0;
     presale_info.max_token_amount_per_address = max_token_amount_per_address;
     presale_info.authority = authority.key();
-    presale_info.is_initialized = true;
-    presale_info.is_active = true;
-    presale_info.is_ended = false;
-    presale_info.is_paused = false;
+    // Sales open with `start_presale` once the presale is funded
+    presale_info.state = PresaleState::Draft;
+    presale_info.finalized_at = 0;
+    presale_info.claim_start_time = 0;
     presale_info.display_end_time = display_end_time;
+    presale_info.unsold_token_policy = unsold_token_policy;
+    presale_info.unsold_tokens = 0;
+    presale_info.softcap_lamports = softcap_lamports;
+    presale_info.total_raised = 0;
+    presale_info.vault_bump = ctx.bumps.presale_vault;
+    presale_info.total_raised_tokens = vec![0; payment_mints.len()];
+    presale_info.payment_mints = payment_mints;
+    presale_info.price_feed = price_feed;
+    presale_info.kyc_signer = kyc_signer;
+    require!(
+        presale_info.is_valid_payment_mints(),
+        PresaleError::InvalidPaymentMints
+    );
+    require!(
+        presale_info.is_valid_softcap(),
+        PresaleError::SoftcapWithPaymentMints
+    );
     // Validate phase allocation
     require!(
         presale_info.validate_phase_allocation(),
         PresaleError::InvalidPhaseAllocation
     );
+    // Without a final end time a raise below the softcap could never be refunded
+    require!(
+        softcap_lamports == 0 || presale_info.phases.last().is_some_and(|p| p.end_time.is_some()),
+        PresaleError::MissingEndTime
+    );
+
+    // Fund the vault's rent exemption once so raised SOL can always be withdrawn in full
+    let rent_minimum = Rent::get()?.minimum_balance(0);
+    let vault_balance = ctx.accounts.presale_vault.lamports();
+    if vault_balance < rent_minimum {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.presale_vault.to_account_info(),
+                },
+            ),
+            rent_minimum - vault_balance,
+        )?;
+    }
 
-    msg!("Presale initialized for token: {}", presale_info.token_mint_address);
-    msg!("Starting with Phase 1: {} tokens @ {} lamports", phases[0].amount, phases[0].price);
+    msg!("Presale {} initialized for token: {}", presale_id, presale_info.token_mint_address);
+    let first_phase = &presale_info.phases[0];
+    msg!("Presale configured with {} phases", presale_info.phase_count());
+    for mint in presale_info.payment_mints.iter() {
+        msg!("Accepting payment in mint {}", mint);
+    }
+    let price_unit = if presale_info.is_usd_priced() { "USD micro-units" } else { "lamports" };
+    msg!("Starting with Phase 1: {} tokens @ {} {}", first_phase.amount, first_phase.price, price_unit);
     msg!("Soft cap: {} tokens, Hard cap: {} tokens", 
-        phases[0].softcap / DECIMALS_MULTIPLIER,
-        phases[0].hardcap / DECIMALS_MULTIPLIER
+        first_phase.softcap / DECIMALS_MULTIPLIER,
+        first_phase.hardcap / DECIMALS_MULTIPLIER
     );
 
     Ok(())
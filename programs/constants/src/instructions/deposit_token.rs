@@ -1,11 +1,7 @@
-use anchor_lang::system_program;
-// use solana_program::program::invoke;
-
 use anchor_spl::token::Token;
 use {anchor_lang::prelude::*, anchor_spl::token};
-// use solana_program::rent::Rent;
 use anchor_spl::associated_token::AssociatedToken;
-use crate::constants::RENT_MINIMUM;
+use crate::errors::PresaleError;
 use crate::state::PresaleInfo;
 
 #[derive(Accounts)]
@@ -39,35 +35,25 @@ pub struct DepositToken<'info> {
     )]
     pub to_associated_token_account: Account<'info, token::TokenAccount>,
 
-    /// CHECK: This is not dangerous
-    #[account(
-        mut,
-        seeds = [
-            b"vault"
-        ],
-        bump,
-    )]
-    pub presale_vault: AccountInfo<'info>,
-
     #[account(
         mut,
         seeds = [
             b"presale",
-            presale_info.authority.as_ref()
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
         ],        
         bump
     )]
     pub presale_info: Box<Account<'info, PresaleInfo>>,
 
-    // #[account(mut)]
-    // pub payer: AccountInfo<'info>,
-    pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+// Can be called any number of times, the presale token account is only created by the first deposit
 pub fn deposit_token(ctx: Context<DepositToken>, amount: u64) -> Result<()> {
+    require!(amount > 0, PresaleError::InvalidAmount);
     let presale_info = &mut ctx.accounts.presale_info;
 
     // Detailed logging for debugging
@@ -82,7 +68,6 @@ pub fn deposit_token(ctx: Context<DepositToken>, amount: u64) -> Result<()> {
         "To Token Address: {}",
         &ctx.accounts.to_associated_token_account.key()
     );
-    msg!("Rent Minimum Transfer Amount: {}", RENT_MINIMUM);
 
     token::transfer(
         CpiContext::new(
@@ -96,23 +81,6 @@ pub fn deposit_token(ctx: Context<DepositToken>, amount: u64) -> Result<()> {
         amount,
     )?;
 
-    // transfer Sol to the presaleVault
-    msg!("From Wallet Address: {}", &ctx.accounts.token_account.key());
-    msg!(
-        "To Wallet Address: {}",
-        &ctx.accounts.to_associated_token_account.key()
-    );
-    system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.admin.to_account_info(),
-                to: ctx.accounts.presale_vault.to_account_info(),
-            },
-        ),
-        RENT_MINIMUM,
-    )?;
-
     // Use the deposit_tokens method from PresaleInfo
     presale_info.deposit_tokens(amount)?;
 
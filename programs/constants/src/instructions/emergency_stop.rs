@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::PresaleInfo;
+use crate::state::{PresaleInfo, PresaleState};
 use crate::errors::PresaleError;
 
 #[derive(Accounts)]
@@ -8,7 +8,8 @@ pub struct EmergencyStop<'info> {
         mut,
         seeds = [
             b"presale",
-            presale_info.authority.as_ref()
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
         ],
         bump,
     )]
@@ -23,13 +24,8 @@ pub struct EmergencyStop<'info> {
 pub fn emergency_stop(ctx: Context<EmergencyStop>) -> Result<()> {
     let presale_info = &mut ctx.accounts.presale_info;
 
-    // Only allow stopping if presale is active and not already paused
-    require!(
-        presale_info.is_active && !presale_info.is_paused,
-        PresaleError::PresalePaused
-    );
-
-    presale_info.is_paused = true;
+    // Only a live presale can be stopped
+    presale_info.transition_to(PresaleState::Paused)?;
     msg!("Presale has been emergency stopped by admin");
 
     Ok(())
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::PresaleInfo;
+use crate::errors::PresaleError;
+
+#[derive(Accounts)]
+pub struct FinalizePresale<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        constraint = authority.key() == presale_info.authority @ PresaleError::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn finalize_presale(ctx: Context<FinalizePresale>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let presale_info = &mut ctx.accounts.presale_info;
+
+    presale_info.finalize(now)?;
+
+    msg!("Presale finalized, claims and SOL withdrawal unlocked");
+    msg!("Total tokens sold: {}", presale_info.total_tokens_sold);
+    msg!("Total raised: {} lamports", presale_info.total_raised);
+    msg!("Unsold tokens to release: {}", presale_info.unsold_tokens);
+
+    Ok(())
+}
@@ -1,15 +1,39 @@
 pub mod create_presale;
 pub mod deposit_token;
 pub mod buy_token;
+pub mod buy_with_sol;
+pub mod buy_with_token;
 pub mod claim_token;
+pub mod claim_all;
 pub mod withdraw_sol;
+pub mod withdraw_payment_tokens;
+pub mod withdraw_tokens;
 pub mod emergency_stop;
 pub mod resume_presale;
+pub mod release_unsold_tokens;
+pub mod refund;
+pub mod refund_token;
+pub mod start_presale;
+pub mod finalize_presale;
+pub mod cancel_presale;
+pub mod set_claim_start_time;
 
 pub use create_presale::*;
 pub use deposit_token::*;
 pub use buy_token::*;
+pub use buy_with_sol::*;
+pub use buy_with_token::*;
 pub use claim_token::*;
+pub use claim_all::*;
 pub use withdraw_sol::*;
+pub use withdraw_payment_tokens::*;
+pub use withdraw_tokens::*;
 pub use emergency_stop::*;
 pub use resume_presale::*;
+pub use release_unsold_tokens::*;
+pub use refund::*;
+pub use refund_token::*;
+pub use start_presale::*;
+pub use finalize_presale::*;
+pub use cancel_presale::*;
+pub use set_claim_start_time::*;
@@ -0,0 +1,85 @@
+use anchor_lang::{prelude::*, system_program};
+
+use crate::errors::PresaleError;
+use crate::state::{PresaleInfo, UserInfo};
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub presale_info: Box<Account<'info, PresaleInfo>>,
+
+    #[account(
+        mut,
+        seeds = [
+            UserInfo::SEED_PREFIX,
+            presale_info.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump,
+        constraint = user_info.wallet == buyer.key() @ PresaleError::InvalidAuthority
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    /// CHECK: Vault PDA where SOL is stored
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            presale_info.key().as_ref()
+        ],
+        bump = presale_info.vault_bump
+    )]
+    pub presale_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Returns the SOL a buyer paid once the presale has ended below its softcap,
+// and zeroes their allocation so nothing can be claimed afterwards. Payments in
+// SPL mints are returned by `refund_token`.
+pub fn refund(ctx: Context<Refund>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let presale_info = &mut ctx.accounts.presale_info;
+    let user_info = &mut ctx.accounts.user_info;
+
+    require!(presale_info.is_refund_available(now), PresaleError::RefundNotAvailable);
+    require!(user_info.has_refundable_purchase(), PresaleError::NothingToRefund);
+    require!(!user_info.has_claimed_any(), PresaleError::UserAlreadyClaimed);
+
+    let (tokens, lamports) = user_info.clear_for_refund();
+    presale_info.record_refund(tokens, lamports)?;
+
+    if lamports > 0 {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.presale_vault.to_account_info(),
+                    to: ctx.accounts.buyer.to_account_info(),
+                },
+                &[&[
+                    b"vault",
+                    presale_info.key().as_ref(),
+                    &[presale_info.vault_bump],
+                ]],
+            ),
+            lamports,
+        )?;
+    }
+
+    msg!("Refunded {} lamports for {} tokens", lamports, tokens);
+    msg!("Presale raised after refund: {} lamports", presale_info.total_raised);
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use {
+    anchor_lang::prelude::*,
+    anchor_spl::token,
+};
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+
+use crate::errors::PresaleError;
+use crate::state::{PresaleInfo, UserInfo};
+
+#[derive(Accounts)]
+pub struct RefundToken<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub presale_info: Box<Account<'info, PresaleInfo>>,
+
+    #[account(
+        mut,
+        seeds = [
+            UserInfo::SEED_PREFIX,
+            presale_info.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump,
+        constraint = user_info.wallet == buyer.key() @ PresaleError::InvalidAuthority
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(
+        constraint = presale_info.payment_mint_index(&payment_mint.key()).is_some()
+            @ PresaleError::PaymentMintNotAccepted
+    )]
+    pub payment_mint: Box<Account<'info, token::Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = presale_info,
+    )]
+    pub presale_payment_account: Box<Account<'info, token::TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_payment_account: Box<Account<'info, token::TokenAccount>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Returns what a buyer paid in one SPL payment mint once refunds are available,
+// and zeroes their allocation if `refund` has not already done so.
+pub fn refund_token(ctx: Context<RefundToken>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let presale_info = &mut ctx.accounts.presale_info;
+    let user_info = &mut ctx.accounts.user_info;
+
+    require!(presale_info.is_refund_available(now), PresaleError::RefundNotAvailable);
+    require!(!user_info.has_claimed_any(), PresaleError::UserAlreadyClaimed);
+
+    let mint_index = presale_info
+        .payment_mint_index(&ctx.accounts.payment_mint.key())
+        .ok_or(PresaleError::PaymentMintNotAccepted)?;
+    let amount = user_info.take_token_refund(mint_index);
+    require!(amount > 0, PresaleError::NothingToRefund);
+    presale_info.record_token_refund(mint_index, amount)?;
+
+    // Any SOL paid stays recorded for `refund`
+    let tokens = user_info.clear_allocation();
+    presale_info.record_refund(tokens, 0)?;
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.presale_payment_account.to_account_info(),
+                to: ctx.accounts.buyer_payment_account.to_account_info(),
+                authority: presale_info.to_account_info(),
+            },
+            &[&[
+                b"presale",
+                presale_info.authority.as_ref(),
+                &presale_info.presale_id.to_le_bytes(),
+                &[ctx.bumps.presale_info],
+            ]],
+        ),
+        amount,
+    )?;
+
+    msg!("Refunded {} of mint {}", amount, ctx.accounts.payment_mint.key());
+
+    Ok(())
+}
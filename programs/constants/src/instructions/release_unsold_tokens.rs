@@ -0,0 +1,108 @@
+use {
+    anchor_lang::prelude::*,
+    anchor_spl::token,
+};
+
+use crate::errors::PresaleError;
+use crate::state::{PresaleInfo, UnsoldTokenPolicy};
+use anchor_spl::token::Token;
+use anchor_spl::associated_token::AssociatedToken;
+
+#[derive(Accounts)]
+pub struct ReleaseUnsoldTokens<'info> {
+    #[account(
+        mut,
+        constraint = token_mint.key() == presale_info.token_mint_address
+    )]
+    pub token_mint: Box<Account<'info, token::Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = presale_info,
+    )]
+    pub presale_associated_token_account: Box<Account<'info, token::TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = authority,
+    )]
+    pub authority_token_account: Box<Account<'info, token::TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub presale_info: Box<Account<'info, PresaleInfo>>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == presale_info.authority @ PresaleError::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Returns or burns, per the presale's unsold token policy, every token released
+// from the sale by phases that closed by time without selling out.
+pub fn release_unsold_tokens(ctx: Context<ReleaseUnsoldTokens>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let presale_info = &mut ctx.accounts.presale_info;
+
+    // Phases may have expired since the last purchase
+    presale_info.close_expired_phases(now)?;
+
+    let amount = presale_info.take_unsold_tokens()?;
+    let policy = presale_info.unsold_token_policy;
+
+    let authority_key = ctx.accounts.presale_info.authority;
+    let presale_id = ctx.accounts.presale_info.presale_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"presale",
+        authority_key.as_ref(),
+        &presale_id,
+        &[ctx.bumps.presale_info],
+    ]];
+
+    if policy == UnsoldTokenPolicy::Burn {
+        token::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.presale_associated_token_account.to_account_info(),
+                    authority: ctx.accounts.presale_info.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+        msg!("Burned {} unsold tokens", amount);
+    } else {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.presale_associated_token_account.to_account_info(),
+                    to: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: ctx.accounts.presale_info.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+        msg!("Returned {} unsold tokens to the authority", amount);
+    }
+
+    Ok(())
+}
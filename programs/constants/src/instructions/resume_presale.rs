@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::PresaleInfo;
+use crate::state::{PresaleInfo, PresaleState};
 use crate::errors::PresaleError;
 
 #[derive(Accounts)]
@@ -8,7 +8,8 @@ pub struct ResumePresale<'info> {
         mut,
         seeds = [
             b"presale",
-            presale_info.authority.as_ref()
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
         ],
         bump,
     )]
@@ -23,13 +24,8 @@ pub struct ResumePresale<'info> {
 pub fn resume_presale(ctx: Context<ResumePresale>, display_end_time: i64) -> Result<()> {
     let presale_info = &mut ctx.accounts.presale_info;
 
-    // Only allow resuming if presale is paused and not ended
-    require!(
-        presale_info.is_paused && !presale_info.is_ended,
-        PresaleError::PresalePaused
-    );
-
-    presale_info.is_paused = false;
+    // Only a paused presale can be resumed
+    presale_info.transition_to(PresaleState::Live)?;
     presale_info.display_end_time = display_end_time;
     
     msg!("Presale has been resumed by admin");
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::PresaleInfo;
+use crate::errors::PresaleError;
+
+#[derive(Accounts)]
+pub struct SetClaimStartTime<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        constraint = authority.key() == presale_info.authority @ PresaleError::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_claim_start_time(ctx: Context<SetClaimStartTime>, claim_start_time: i64) -> Result<()> {
+    let presale_info = &mut ctx.accounts.presale_info;
+
+    presale_info.set_claim_start_time(claim_start_time)?;
+    msg!("Claim start time set to: {}", claim_start_time);
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::{PresaleInfo, PresaleState};
+use crate::errors::PresaleError;
+
+#[derive(Accounts)]
+pub struct StartPresale<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        constraint = authority.key() == presale_info.authority @ PresaleError::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn start_presale(ctx: Context<StartPresale>) -> Result<()> {
+    let presale_info = &mut ctx.accounts.presale_info;
+
+    // Only a draft presale can go live
+    presale_info.transition_to(PresaleState::Live)?;
+    msg!("Presale is live, Phase {} open for purchases", presale_info.current_phase);
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use {
+    anchor_lang::prelude::*,
+    anchor_spl::token,
+};
+
+use crate::errors::PresaleError;
+use crate::state::{PresaleInfo, PresaleState};
+use anchor_spl::token::Token;
+use anchor_spl::associated_token::AssociatedToken;
+
+#[derive(Accounts)]
+pub struct WithdrawPaymentTokens<'info> {
+    #[account(
+        constraint = presale_info.payment_mint_index(&payment_mint.key()).is_some()
+            @ PresaleError::PaymentMintNotAccepted
+    )]
+    pub payment_mint: Box<Account<'info, token::Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = presale_info,
+    )]
+    pub presale_payment_account: Box<Account<'info, token::TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = payment_mint,
+        associated_token::authority = authority,
+    )]
+    pub authority_payment_account: Box<Account<'info, token::TokenAccount>>,
+
+    #[account(
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub presale_info: Box<Account<'info, PresaleInfo>>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == presale_info.authority @ PresaleError::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Sends everything raised in one SPL payment mint to the authority. Like SOL,
+// payments stay in the presale to fund refunds until it is finalized.
+pub fn withdraw_payment_tokens(ctx: Context<WithdrawPaymentTokens>) -> Result<()> {
+    let presale_info = &ctx.accounts.presale_info;
+    require!(
+        presale_info.state == PresaleState::Finalized,
+        PresaleError::PresaleNotFinalized
+    );
+
+    let amount = ctx.accounts.presale_payment_account.amount;
+    require!(amount > 0, PresaleError::EmptyVault);
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.presale_payment_account.to_account_info(),
+                to: ctx.accounts.authority_payment_account.to_account_info(),
+                authority: presale_info.to_account_info(),
+            },
+            &[&[
+                b"presale",
+                presale_info.authority.as_ref(),
+                &presale_info.presale_id.to_le_bytes(),
+                &[ctx.bumps.presale_info],
+            ]],
+        ),
+        amount,
+    )?;
+
+    msg!("Withdrew {} of mint {} to the authority", amount, ctx.accounts.payment_mint.key());
+
+    Ok(())
+}
@@ -7,7 +7,8 @@ pub struct WithdrawSol<'info> {
     #[account(
         seeds = [
             b"presale",
-            presale_info.authority.as_ref()
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
         ],
         bump
     )]
@@ -16,8 +17,11 @@ pub struct WithdrawSol<'info> {
     /// CHECK: Vault PDA where SOL is stored
     #[account(
         mut,
-        seeds = [b"vault"],
-        bump
+        seeds = [
+            b"vault",
+            presale_info.key().as_ref()
+        ],
+        bump = presale_info.vault_bump
     )]
     pub presale_vault: AccountInfo<'info>,
 
@@ -30,17 +34,23 @@ pub struct WithdrawSol<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn withdraw_sol(ctx: Context<WithdrawSol>, bump: u8) -> Result<()> {
-    // Fetch the vault's SOL balance
+pub fn withdraw_sol(ctx: Context<WithdrawSol>) -> Result<()> {
+    // Fetch the vault's SOL balance, keeping it rent exempt
     let vault_balance = ctx.accounts.presale_vault.to_account_info().lamports();
-    msg!("Vault balance: {} lamports", vault_balance);
+    let withdrawable = vault_balance.saturating_sub(Rent::get()?.minimum_balance(0));
+    msg!("Vault balance: {} lamports, withdrawable: {}", vault_balance, withdrawable);
 
     // Check if there's NO balance to withdraw
-    require!(vault_balance > 0, PresaleError::EmptyVault);
+    require!(withdrawable > 0, PresaleError::EmptyVault);
     // check if the admin is the authority of the presale
     require!(ctx.accounts.admin.key() == ctx.accounts.presale_info.authority, PresaleError::InvalidAuthority);
+    // Raised SOL stays in the vault to fund refunds until the presale is finalized
+    require!(
+        ctx.accounts.presale_info.is_sol_withdrawable(),
+        PresaleError::PresaleNotFinalized
+    );
 
-    // Transfer all SOL from the vault to the admin
+    // Transfer all raised SOL from the vault to the admin
     system_program::transfer(
         CpiContext::new_with_signer(
             ctx.accounts.system_program.to_account_info(),
@@ -48,9 +58,13 @@ pub fn withdraw_sol(ctx: Context<WithdrawSol>, bump: u8) -> Result<()> {
                 from: ctx.accounts.presale_vault.to_account_info(),
                 to: ctx.accounts.admin.to_account_info(),
             },
-            &[&[b"vault", &[bump]]],
+            &[&[
+                b"vault",
+                ctx.accounts.presale_info.key().as_ref(),
+                &[ctx.accounts.presale_info.vault_bump],
+            ]],
         ),
-        vault_balance,
+        withdrawable,
     )?;
 
     Ok(())
@@ -0,0 +1,92 @@
+use {
+    anchor_lang::prelude::*,
+    anchor_spl::token,
+};
+
+use crate::errors::PresaleError;
+use crate::state::PresaleInfo;
+use anchor_spl::token::Token;
+use anchor_spl::associated_token::AssociatedToken;
+
+#[derive(Accounts)]
+pub struct WithdrawTokens<'info> {
+    #[account(
+        constraint = token_mint.key() == presale_info.token_mint_address
+    )]
+    pub token_mint: Box<Account<'info, token::Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = presale_info,
+    )]
+    pub presale_associated_token_account: Box<Account<'info, token::TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = authority,
+    )]
+    pub authority_token_account: Box<Account<'info, token::TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"presale",
+            presale_info.authority.as_ref(),
+            &presale_info.presale_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub presale_info: Box<Account<'info, PresaleInfo>>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == presale_info.authority @ PresaleError::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Returns presale tokens to the authority, limited to the surplus above what is
+// still owed to buyers, pending release or, until finalization, still for sale.
+pub fn withdraw_tokens(ctx: Context<WithdrawTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, PresaleError::InvalidAmount);
+
+    let vault_balance = ctx.accounts.presale_associated_token_account.amount;
+    let presale_info = &mut ctx.accounts.presale_info;
+    let surplus = presale_info.withdrawable_surplus(vault_balance);
+    msg!(
+        "Vault balance: {}, owed: {}, withdrawable: {}",
+        vault_balance,
+        presale_info.outstanding_token_obligations(),
+        surplus
+    );
+    presale_info.withdraw_surplus(amount, vault_balance)?;
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.presale_associated_token_account.to_account_info(),
+                to: ctx.accounts.authority_token_account.to_account_info(),
+                authority: presale_info.to_account_info(),
+            },
+            &[&[
+                b"presale",
+                presale_info.authority.as_ref(),
+                &presale_info.presale_id.to_le_bytes(),
+                &[ctx.bumps.presale_info],
+            ]],
+        ),
+        amount,
+    )?;
+
+    msg!("Withdrew {} surplus tokens to the authority", amount);
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ pub mod constants;
 pub mod errors;
 pub mod instructions;
 pub mod state;
+pub mod utils;
 
 use instructions::*;
 
@@ -13,40 +14,113 @@ declare_id!("9gsgUcdSGqCgFCqH8wxgmysSdT9pF33ExWuWjanmrwuo");
 pub mod zexxcoin_presale {
     use super::*;
 
-    pub fn create_presale(
-        ctx: Context<CreatePresale>,
-        token_mint_address: Pubkey,
-        max_token_amount_per_address: u64,
-        display_end_time: i64,
-    ) -> Result<()> {
-        instructions::create_presale::create_presale(
-            ctx,
-            token_mint_address,
-            max_token_amount_per_address,
-            display_end_time,
-        )
+    pub fn create_presale(ctx: Context<CreatePresale>, params: CreatePresaleParams) -> Result<()> {
+        instructions::create_presale::create_presale(ctx, params)
     }
 
     pub fn deposit_token(ctx: Context<DepositToken>, amount: u64) -> Result<()> {
         instructions::deposit_token::deposit_token(ctx, amount)
     }
 
-    pub fn buy_token(ctx: Context<BuyToken>, amount: u64) -> Result<()> {
-        instructions::buy_token::buy_token(ctx, amount)
+    pub fn buy_token(
+        ctx: Context<BuyToken>,
+        beneficiary: Pubkey,
+        amount: u64,
+        max_payment: u64,
+        allowlist_proof: Option<AllowlistProof>,
+        voucher: Option<PurchaseVoucher>,
+    ) -> Result<()> {
+        instructions::buy_token::buy_token(ctx, beneficiary, amount, max_payment, allowlist_proof, voucher)
+    }
+
+    pub fn buy_with_sol(
+        ctx: Context<BuyToken>,
+        beneficiary: Pubkey,
+        lamports_in: u64,
+        min_tokens_out: u64,
+        allowlist_proof: Option<AllowlistProof>,
+        voucher: Option<PurchaseVoucher>,
+    ) -> Result<()> {
+        instructions::buy_with_sol::buy_with_sol(
+            ctx,
+            beneficiary,
+            lamports_in,
+            min_tokens_out,
+            allowlist_proof,
+            voucher,
+        )
+    }
+
+    pub fn buy_with_token(
+        ctx: Context<BuyWithToken>,
+        beneficiary: Pubkey,
+        amount: u64,
+        max_payment: u64,
+        allowlist_proof: Option<AllowlistProof>,
+        voucher: Option<PurchaseVoucher>,
+    ) -> Result<()> {
+        instructions::buy_with_token::buy_with_token(
+            ctx,
+            beneficiary,
+            amount,
+            max_payment,
+            allowlist_proof,
+            voucher,
+        )
     }
 
     pub fn claim_token(ctx: Context<ClaimToken>, phase_to_claim: u8) -> Result<()> {
         instructions::claim_token::claim_token(ctx, phase_to_claim)
     }
 
-    pub fn withdraw_sol(ctx: Context<WithdrawSol>, bump: u8) -> Result<()> {
-        instructions::withdraw_sol::withdraw_sol(ctx, bump)
+    pub fn claim_all(ctx: Context<ClaimToken>) -> Result<()> {
+        instructions::claim_all::claim_all(ctx)
+    }
+
+    pub fn refund(ctx: Context<Refund>) -> Result<()> {
+        instructions::refund::refund(ctx)
+    }
+
+    pub fn refund_token(ctx: Context<RefundToken>) -> Result<()> {
+        instructions::refund_token::refund_token(ctx)
+    }
+
+    pub fn withdraw_sol(ctx: Context<WithdrawSol>) -> Result<()> {
+        instructions::withdraw_sol::withdraw_sol(ctx)
+    }
+
+    pub fn withdraw_payment_tokens(ctx: Context<WithdrawPaymentTokens>) -> Result<()> {
+        instructions::withdraw_payment_tokens::withdraw_payment_tokens(ctx)
+    }
+
+    pub fn withdraw_tokens(ctx: Context<WithdrawTokens>, amount: u64) -> Result<()> {
+        instructions::withdraw_tokens::withdraw_tokens(ctx, amount)
+    }
+
+    pub fn start_presale(ctx: Context<StartPresale>) -> Result<()> {
+        instructions::start_presale::start_presale(ctx)
+    }
+
+    pub fn finalize_presale(ctx: Context<FinalizePresale>) -> Result<()> {
+        instructions::finalize_presale::finalize_presale(ctx)
+    }
+
+    pub fn cancel_presale(ctx: Context<CancelPresale>) -> Result<()> {
+        instructions::cancel_presale::cancel_presale(ctx)
+    }
+
+    pub fn set_claim_start_time(ctx: Context<SetClaimStartTime>, claim_start_time: i64) -> Result<()> {
+        instructions::set_claim_start_time::set_claim_start_time(ctx, claim_start_time)
     }
 
     pub fn emergency_stop(ctx: Context<EmergencyStop>) -> Result<()> {
         instructions::emergency_stop::emergency_stop(ctx)
     }
 
+    pub fn release_unsold_tokens(ctx: Context<ReleaseUnsoldTokens>) -> Result<()> {
+        instructions::release_unsold_tokens::release_unsold_tokens(ctx)
+    }
+
     pub fn resume_presale(ctx: Context<ResumePresale>, display_end_time: i64) -> Result<()> {
         instructions::resume_presale::resume_presale(ctx, display_end_time)
     }
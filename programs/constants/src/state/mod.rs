@@ -1,7 +1,9 @@
 pub mod presale_info;
 pub mod user_info;
 pub mod phase_info;
+pub mod price_feed;
 
 pub use presale_info::*;
 pub use user_info::*;
-pub use phase_info::*;
\ No newline at end of file
+pub use phase_info::*;
+pub use price_feed::*;
\ No newline at end of file
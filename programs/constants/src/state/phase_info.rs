@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
+use crate::constants::presale_config::{DECIMALS_MULTIPLIER, MAX_PAYMENT_MINTS};
+use crate::utils::merkle::{allowlist_leaf, verify_proof};
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
 pub enum PhaseStatus {
     #[default]
     Upcoming = 0,
@@ -18,17 +20,88 @@ impl PhaseStatus {
     }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+// How tokens bought in a phase unlock, measured from the token generation event
+// (TGE). The default schedule unlocks everything at the TGE.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub struct VestingSchedule {
+    pub tge_unlock_bps: u16,    // Share unlocked at the TGE, in basis points (10_000 = 100%)
+    pub cliff_duration: i64,    // Seconds after the TGE before linear release starts
+    pub vesting_duration: i64,  // Seconds over which the rest is released linearly after the cliff
+}
+
+impl VestingSchedule {
+    pub const MAX_BPS: u16 = 10_000;
+
+    pub fn is_valid(&self) -> bool {
+        self.tge_unlock_bps <= Self::MAX_BPS &&
+        self.cliff_duration >= 0 &&
+        self.vesting_duration >= 0
+    }
+
+    // Portion of `total` unlocked `elapsed` seconds after the TGE
+    pub fn vested_amount(&self, total: u64, elapsed: i64) -> u64 {
+        if elapsed < 0 {
+            return 0;
+        }
+        let tge_amount = (total as u128 * self.tge_unlock_bps as u128 / Self::MAX_BPS as u128) as u64;
+        if elapsed < self.cliff_duration {
+            return tge_amount;
+        }
+
+        let linear_total = total - tge_amount;
+        let linear_elapsed = elapsed - self.cliff_duration;
+        if linear_elapsed >= self.vesting_duration {
+            return total;
+        }
+        let linear_vested = linear_total as u128 * linear_elapsed as u128 / self.vesting_duration as u128;
+        tge_amount + linear_vested as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Default, Debug)]
 pub struct Phase {
     pub phase_number: u8,
     pub amount: u64,          // Amount of tokens allocated for this phase
-    pub price: u64,          // Price in lamports
+    pub price: u64,          // Price in lamports, or USD micro-units when the presale has a price feed
     pub percentage: u8,      // Percentage of total tokens
     pub tokens_sold: u64,    // Tokens sold in this phase
     pub tokens_available: u64, // Tokens available for sale in this phase
     pub status: PhaseStatus, // Current status of the phase
     pub softcap: u64,       // Minimum target for the phase (soft cap)
     pub hardcap: u64,       // Maximum limit for the phase (hard cap)
+    pub max_per_wallet: Option<u64>, // Optional per-wallet cap for this phase
+    pub start_time: Option<i64>, // Optional Unix timestamp before which the phase cannot sell
+    pub end_time: Option<i64>,   // Optional Unix timestamp at which the phase closes, sold out or not
+    pub vesting: VestingSchedule, // Unlock schedule for tokens bought in this phase
+    // Price per whole token in base units of each payment mint, in the order of
+    // `PresaleInfo::payment_mints` (0 = that mint is not accepted in this phase)
+    #[max_len(MAX_PAYMENT_MINTS)]
+    pub token_prices: Vec<u64>,
+    // Merkle root of the wallets allowed to buy in this phase, `None` = open to all
+    pub allowlist_root: Option<[u8; 32]>,
+}
+
+// Portion of a purchase filled from a single phase
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhaseFill {
+    pub phase_number: u8,
+    pub amount: u64,   // Tokens taken from this phase
+    pub cost: u64,     // Lamports, or payment mint base units, owed for them at this phase's price
+}
+
+// Phase definition supplied by the authority when creating a presale
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct PhaseConfig {
+    pub amount: u64,         // Amount of tokens allocated for this phase
+    pub price: u64,          // Price in lamports (or USD micro-units with a price feed) per whole token
+    pub min_purchase: u64,   // Minimum purchase amount (becomes the phase softcap)
+    pub max_purchase: u64,   // Maximum purchase amount (becomes the phase hardcap)
+    pub max_per_wallet: Option<u64>, // Optional per-wallet cap for this phase
+    pub start_time: Option<i64>, // Optional Unix timestamp the phase opens at
+    pub end_time: Option<i64>,   // Optional Unix timestamp the phase closes at
+    pub vesting: VestingSchedule, // Unlock schedule for tokens bought in this phase
+    pub token_prices: Vec<u64>, // Price in each payment mint, one entry per presale payment mint
+    pub allowlist_root: Option<[u8; 32]>, // Optional Merkle root of allowed wallets (see `utils::merkle`)
 }
 
 impl Phase {
@@ -40,11 +113,91 @@ impl Phase {
         self.status == PhaseStatus::Active && !self.is_complete()
     }
 
+    pub fn has_started(&self, now: i64) -> bool {
+        match self.start_time {
+            Some(start_time) => now >= start_time,
+            None => true,
+        }
+    }
+
+    pub fn has_expired(&self, now: i64) -> bool {
+        match self.end_time {
+            Some(end_time) => now >= end_time,
+            None => false,
+        }
+    }
+
+    pub fn is_open_at(&self, now: i64) -> bool {
+        self.has_started(now) && !self.has_expired(now)
+    }
+
     pub fn remaining_tokens(&self) -> u64 {
-        if self.tokens_sold >= self.amount {
-            0
-        } else {
-            self.amount - self.tokens_sold
+        self.amount.saturating_sub(self.tokens_sold)
+    }
+
+    // A purchase must reach the phase minimum (softcap), unless it buys out
+    // everything left in the phase and that remainder is below the minimum.
+    pub fn meets_minimum_purchase(&self, amount: u64) -> bool {
+        amount > 0 && (amount >= self.softcap || amount >= self.tokens_available)
+    }
+
+    // Lamports owed for `amount` base units at this phase's price (per whole token).
+    // Computed over base units in u128 and rounded up in favour of the presale,
+    // so any non-zero purchase at a non-zero price costs at least one lamport.
+    pub fn cost_for(&self, amount: u64) -> Option<u64> {
+        Self::cost_at(amount, self.price)
+    }
+
+    // Price per whole token in lamports (`None`) or in the payment mint at
+    // `mint_index` of the presale, 0 if that mint is not accepted in this phase
+    pub fn price_in(&self, mint_index: Option<usize>) -> u64 {
+        match mint_index {
+            None => self.price,
+            Some(index) => self.token_prices.get(index).copied().unwrap_or(0),
         }
     }
+
+    // Cost of `amount` base units at `price` per whole token, rounded up
+    pub fn cost_at(amount: u64, price: u64) -> Option<u64> {
+        let cost = (amount as u128)
+            .checked_mul(price as u128)?
+            .div_ceil(DECIMALS_MULTIPLIER as u128);
+        u64::try_from(cost).ok()
+    }
+
+    // Whole base units `lamports` can buy at this phase's price, rounded down
+    // and capped at what is still available in the phase.
+    pub fn tokens_for_lamports(&self, lamports: u64) -> Option<u64> {
+        let tokens = (lamports as u128)
+            .checked_mul(DECIMALS_MULTIPLIER as u128)?
+            .checked_div(self.price as u128)?;
+        Some(u64::try_from(tokens).unwrap_or(u64::MAX).min(self.tokens_available))
+    }
+
+    pub fn is_within_wallet_cap(&self, wallet_phase_total: u64) -> bool {
+        match self.max_per_wallet {
+            Some(cap) => wallet_phase_total <= cap,
+            None => true,
+        }
+    }
+
+    pub fn is_allowlisted(&self, wallet: &Pubkey, max_allocation: u64, proof: &[[u8; 32]]) -> bool {
+        match &self.allowlist_root {
+            Some(root) => verify_proof(proof, root, allowlist_leaf(wallet, max_allocation)),
+            None => true,
+        }
+    }
+
+    pub fn is_valid_config(&self) -> bool {
+        self.amount > 0 &&
+        self.price > 0 &&
+        self.hardcap > 0 &&
+        self.softcap <= self.hardcap &&
+        self.hardcap <= self.amount &&
+        self.max_per_wallet != Some(0) &&
+        self.vesting.is_valid() &&
+        !matches!((self.start_time, self.end_time), (Some(start), Some(end)) if start >= end) &&
+        self.tokens_sold == 0 &&
+        self.tokens_available == self.amount
+    }
 } 
\ No newline at end of file
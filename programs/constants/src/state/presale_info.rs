@@ -1,47 +1,156 @@
 use anchor_lang::prelude::*;
-use crate::state::phase_info::{Phase, PhaseStatus};
+use crate::state::phase_info::{Phase, PhaseFill, PhaseStatus};
+use crate::state::price_feed::PriceFeedConfig;
+use crate::state::user_info::UserInfo;
 use crate::errors::PresaleError;
-use crate::constants::presale_config::TOTAL_SUPPLY;
+use crate::constants::presale_config::{MAX_PAYMENT_MINTS, MAX_PHASES};
+
+// What happens to the tokens a phase leaves unsold when it closes by time
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub enum UnsoldTokenPolicy {
+    // Added to the next phase's allocation (returned to the authority after the final phase)
+    #[default]
+    RollOver = 0,
+    // Released from the sale and sent back to the authority
+    ReturnToAuthority = 1,
+    // Released from the sale and burned
+    Burn = 2,
+}
+
+// Presale lifecycle. Finalized and Cancelled are terminal.
+//
+//   Draft -> Live <-> Paused
+//   Live | Paused -> Finalized
+//   Draft | Live | Paused -> Cancelled
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub enum PresaleState {
+    // Created, being funded, not selling yet
+    #[default]
+    Draft = 0,
+    // Accepting purchases
+    Live = 1,
+    // Emergency stopped by the authority
+    Paused = 2,
+    // Sales closed, claims and SOL withdrawal unlocked
+    Finalized = 3,
+    // Called off by the authority, buyers can refund
+    Cancelled = 4,
+}
+
+impl PresaleState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PresaleState::Draft => "Draft",
+            PresaleState::Live => "Live",
+            PresaleState::Paused => "Paused",
+            PresaleState::Finalized => "Finalized",
+            PresaleState::Cancelled => "Cancelled",
+        }
+    }
+
+    pub fn can_transition_to(&self, next: PresaleState) -> bool {
+        matches!(
+            (self, next),
+            (PresaleState::Draft, PresaleState::Live)
+                | (PresaleState::Live, PresaleState::Paused)
+                | (PresaleState::Paused, PresaleState::Live)
+                | (PresaleState::Live, PresaleState::Finalized)
+                | (PresaleState::Paused, PresaleState::Finalized)
+                | (PresaleState::Draft, PresaleState::Cancelled)
+                | (PresaleState::Live, PresaleState::Cancelled)
+                | (PresaleState::Paused, PresaleState::Cancelled)
+        )
+    }
+}
 
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct PresaleInfo {
+    // Identifier chosen by the authority, lets one authority run several presales
+    pub presale_id: u64,
     // Mint address of the presale token
     pub token_mint_address: Pubkey,
-    // Total token supply for presale (1,000,000)
+    // Total token supply for presale (sum of all phase allocations)
     pub total_token_supply: u64,
     // Remaining tokens to be sold across all phases
     pub remaining_tokens: u64,
-    // Current active phase (1-6)
+    // Current active phase (1-based)
     pub current_phase: u8,
-    // Array of phase information
-    pub phases: [Phase; 6],
+    // Phase information, sized from the phase count at creation (see `space`)
+    #[max_len(0)]
+    pub phases: Vec<Phase>,
     // Total amount of presale tokens sold during the presale
     pub total_tokens_sold: u64,
     // Total amount of tokens deposited by admin
     pub total_tokens_deposited: u64,
-    // Maximum amount of presale tokens an address can purchase
+    // Total amount of sold tokens claimed by buyers
+    pub total_tokens_claimed: u64,
+    // Maximum amount of presale tokens an address can purchase across all phases
     pub max_token_amount_per_address: u64,
     // Authority of the presale
     pub authority: Pubkey,
-    // Presale is initialized and ready
-    pub is_initialized: bool,
-    // Presale is currently active
-    pub is_active: bool,
-    // Presale has ended
-    pub is_ended: bool,
-    // Emergency stop flag
-    pub is_paused: bool,
+    // Lifecycle state of the presale
+    pub state: PresaleState,
+    // When the presale was finalized (Unix timestamp), 0 until then
+    pub finalized_at: i64,
+    // Token generation event: claims open at this Unix timestamp (0 = at finalization)
+    pub claim_start_time: i64,
     // Display end time (Unix timestamp) - for showcase only
     pub display_end_time: i64,
+    // Policy applied to a phase's unsold tokens when it closes by time
+    pub unsold_token_policy: UnsoldTokenPolicy,
+    // Unsold tokens released from the sale, waiting to be returned or burned
+    pub unsold_tokens: u64,
+    // Minimum lamports the presale must raise, otherwise buyers are refunded (0 = none)
+    pub softcap_lamports: u64,
+    // Lamports raised by purchases, net of refunds
+    pub total_raised: u64,
+    // Canonical bump of this presale's SOL vault PDA [b"vault", presale_info]
+    pub vault_bump: u8,
+    // SPL mints accepted as payment besides SOL, paid into the presale's associated token accounts
+    #[max_len(MAX_PAYMENT_MINTS)]
+    pub payment_mints: Vec<Pubkey>,
+    // Amount raised in each payment mint, net of refunds, in the order of `payment_mints`
+    #[max_len(MAX_PAYMENT_MINTS)]
+    pub total_raised_tokens: Vec<u64>,
+    // SOL/USD oracle, when set phase prices are in USD micro-units instead of lamports
+    pub price_feed: Option<PriceFeedConfig>,
+    // Compliance key that must sign a voucher for every purchase, `None` = no KYC
+    pub kyc_signer: Option<Pubkey>,
 }
 
 impl PresaleInfo {
-    pub const TOTAL_PHASES: usize = 6;
-    pub const TOTAL_SUPPLY: u64 = TOTAL_SUPPLY;
+    pub fn space(phase_count: usize) -> usize {
+        8 + Self::INIT_SPACE + phase_count * Phase::INIT_SPACE
+    }
+
+    pub fn is_valid_phase_count(phase_count: usize) -> bool {
+        phase_count > 0 && phase_count <= MAX_PHASES
+    }
+
+    pub fn phase_count(&self) -> u8 {
+        self.phases.len() as u8
+    }
+
+    pub fn is_usd_priced(&self) -> bool {
+        self.price_feed.is_some()
+    }
+
+    pub fn payment_mint_index(&self, mint: &Pubkey) -> Option<usize> {
+        self.payment_mints.iter().position(|payment_mint| payment_mint == mint)
+    }
+
+    // At most MAX_PAYMENT_MINTS distinct mints, none of them the presale token
+    pub fn is_valid_payment_mints(&self) -> bool {
+        self.payment_mints.len() <= MAX_PAYMENT_MINTS &&
+        !self.payment_mints.contains(&self.token_mint_address) &&
+        self.payment_mints.iter()
+            .enumerate()
+            .all(|(i, mint)| !self.payment_mints[..i].contains(mint))
+    }
 
     pub fn get_current_phase(&self) -> Option<&Phase> {
-        if self.current_phase == 0 || self.current_phase > Self::TOTAL_PHASES as u8 {
+        if self.current_phase == 0 || self.current_phase > self.phase_count() {
             None
         } else {
             Some(&self.phases[(self.current_phase - 1) as usize])
@@ -49,7 +158,7 @@ impl PresaleInfo {
     }
 
     pub fn get_current_phase_mut(&mut self) -> Option<&mut Phase> {
-        if self.current_phase == 0 || self.current_phase > Self::TOTAL_PHASES as u8 {
+        if self.current_phase == 0 || self.current_phase > self.phase_count() {
             None
         } else {
             Some(&mut self.phases[(self.current_phase - 1) as usize])
@@ -58,17 +167,37 @@ impl PresaleInfo {
 
     pub fn can_move_to_next_phase(&self) -> bool {
         if let Some(current_phase) = self.get_current_phase() {
-            current_phase.is_complete() && self.current_phase < Self::TOTAL_PHASES as u8
+            current_phase.is_complete() && self.current_phase < self.phase_count()
         } else {
             false
         }
     }
 
     pub fn validate_phase_allocation(&self) -> bool {
-        let total_percentage: u8 = self.phases.iter().map(|p| p.percentage).sum();
-        let total_tokens: u64 = self.phases.iter().map(|p| p.amount).sum();
-        
-        total_percentage == 100 && total_tokens == Self::TOTAL_SUPPLY
+        let phases_valid = self.phases.iter()
+            .enumerate()
+            .all(|(i, p)| {
+                p.phase_number as usize == i + 1 &&
+                p.is_valid_config() &&
+                p.token_prices.len() == self.payment_mints.len()
+            });
+        let total_percentage: u16 = self.phases.iter().map(|p| p.percentage as u16).sum();
+        let total_tokens = self.phases.iter()
+            .try_fold(0u64, |acc, p| acc.checked_add(p.amount));
+        // A phase may not open before the previous one has closed
+        let schedule_ordered = self.phases.windows(2).all(|pair| {
+            match (pair[0].end_time, pair[1].start_time) {
+                (Some(previous_end), Some(next_start)) => next_start >= previous_end,
+                _ => true,
+            }
+        });
+
+        phases_valid &&
+        schedule_ordered &&
+        Self::is_valid_phase_count(self.phases.len()) &&
+        self.total_token_supply > 0 &&
+        total_percentage <= 100 &&
+        total_tokens == Some(self.total_token_supply)
     }
 
     pub fn is_valid_purchase_amount(&self, amount: u64) -> bool {
@@ -79,17 +208,19 @@ impl PresaleInfo {
         }
     }
 
+    // Ends the current phase once it is complete and activates the next one.
+    // The final phase is ended in place, `current_phase` never points past it.
     pub fn update_phase_status(&mut self) -> bool {
         let current_phase_num = self.current_phase;
-        if current_phase_num == 0 || current_phase_num >= Self::TOTAL_PHASES as u8 {
+        if current_phase_num == 0 || current_phase_num > self.phase_count() {
             return false;
         }
 
         let current_phase = &mut self.phases[(current_phase_num - 1) as usize];
         if current_phase.is_complete() {
             current_phase.status = PhaseStatus::Ended;
-            self.current_phase += 1;
-            if self.current_phase <= Self::TOTAL_PHASES as u8 {
+            if current_phase_num < self.phase_count() {
+                self.current_phase += 1;
                 self.phases[self.current_phase as usize - 1].status = PhaseStatus::Active;
             }
             true
@@ -98,12 +229,222 @@ impl PresaleInfo {
         }
     }
 
+    // Closes every phase whose end time has passed, moving `current_phase` on to
+    // the next one. Returns the numbers of the phases closed by time.
+    pub fn sync_phases(&mut self, now: i64) -> Vec<u8> {
+        let mut closed = Vec::new();
+        loop {
+            let current_phase_num = self.current_phase;
+            let phase_count = self.phase_count();
+            let Some(phase) = self.get_current_phase_mut() else {
+                break;
+            };
+            if phase.status == PhaseStatus::Ended || !phase.has_expired(now) {
+                break;
+            }
+
+            phase.status = PhaseStatus::Ended;
+            closed.push(current_phase_num);
+            msg!("Phase {} closed by time", current_phase_num);
+            if current_phase_num >= phase_count {
+                break;
+            }
+            self.current_phase += 1;
+            self.phases[current_phase_num as usize].status = PhaseStatus::Active;
+        }
+        closed
+    }
+
+    // Closes expired phases and applies the unsold token policy to each of them,
+    // in phase order so rolled over tokens can roll on through several phases.
+    pub fn close_expired_phases(&mut self, now: i64) -> Result<()> {
+        for phase_number in self.sync_phases(now) {
+            self.release_phase_leftover(phase_number)?;
+        }
+        Ok(())
+    }
+
+    // Moves whatever an ended phase left unsold out of it according to the policy,
+    // keeping `remaining_tokens` equal to the sum of every phase's `tokens_available`.
+    pub fn release_phase_leftover(&mut self, phase_number: u8) -> Result<()> {
+        let index = (phase_number - 1) as usize;
+        let leftover = self.phases[index].tokens_available;
+        if leftover == 0 {
+            return Ok(());
+        }
+
+        let phase = &mut self.phases[index];
+        phase.tokens_available = 0;
+        phase.amount = phase.amount.checked_sub(leftover)
+            .ok_or(PresaleError::Overflow)?;
+
+        let has_next_phase = phase_number < self.phase_count();
+        if self.unsold_token_policy == UnsoldTokenPolicy::RollOver && has_next_phase {
+            let next_phase = &mut self.phases[index + 1];
+            next_phase.amount = next_phase.amount.checked_add(leftover)
+                .ok_or(PresaleError::Overflow)?;
+            next_phase.tokens_available = next_phase.tokens_available.checked_add(leftover)
+                .ok_or(PresaleError::Overflow)?;
+            msg!("Rolled {} unsold tokens from phase {} into phase {}", leftover, phase_number, phase_number + 1);
+        } else {
+            self.remaining_tokens = self.remaining_tokens.checked_sub(leftover)
+                .ok_or(PresaleError::Overflow)?;
+            self.unsold_tokens = self.unsold_tokens.checked_add(leftover)
+                .ok_or(PresaleError::Overflow)?;
+            msg!("Released {} unsold tokens from phase {}", leftover, phase_number);
+        }
+        Ok(())
+    }
+
+    // True once the final phase has closed by time
+    pub fn is_time_expired(&self, now: i64) -> bool {
+        self.phases.last().is_some_and(|phase| phase.has_expired(now))
+    }
+
+    // Splits a purchase of `amount` tokens across the current phase and, once it
+    // sells out, the following phases at their own prices. Only phases open at
+    // `now` are filled, each fill must stay within its own phase's minimum and
+    // maximum purchase. Does not mutate state.
+    pub fn plan_purchase(&self, amount: u64, now: i64) -> Result<Vec<PhaseFill>> {
+        self.plan_purchase_in(amount, now, None)
+    }
+
+    // Same as `plan_purchase`, priced in lamports (`None`) or in the payment mint
+    // at `mint_index`. Every phase filled must accept that mint.
+    pub fn plan_purchase_in(&self, amount: u64, now: i64, mint_index: Option<usize>) -> Result<Vec<PhaseFill>> {
+        let current_phase = self.get_current_phase().ok_or(PresaleError::InvalidPhase)?;
+        require!(!self.is_time_expired(now), PresaleError::PresaleTimeExpired);
+        require!(current_phase.status == PhaseStatus::Active, PresaleError::PhaseNotActive);
+        require!(current_phase.has_started(now), PresaleError::PhaseNotStarted);
+        require!(!current_phase.has_expired(now), PresaleError::PhaseNotActive);
+        require!(amount > 0, PresaleError::BelowSoftcap);
+        require!(amount <= self.remaining_tokens, PresaleError::InsufficientTokens);
+
+        let mut fills = Vec::new();
+        let mut remaining = amount;
+        for phase in &self.phases[(self.current_phase - 1) as usize..] {
+            if remaining == 0 || !phase.is_open_at(now) {
+                break;
+            }
+            let take = remaining.min(phase.tokens_available);
+            if take == 0 {
+                continue;
+            }
+            require!(phase.meets_minimum_purchase(take), PresaleError::BelowSoftcap);
+            require!(take <= phase.hardcap, PresaleError::AboveHardcap);
+            let price = phase.price_in(mint_index);
+            require!(price > 0, PresaleError::PaymentMintNotAccepted);
+            fills.push(PhaseFill {
+                phase_number: phase.phase_number,
+                amount: take,
+                cost: Phase::cost_at(take, price).ok_or(PresaleError::Overflow)?,
+            });
+            remaining -= take;
+        }
+        require!(remaining == 0, PresaleError::InsufficientTokens);
+
+        Ok(fills)
+    }
+
+    // Tokens `lamports` buys starting at the current phase, spilling into later
+    // phases at their prices. Matches the split `plan_purchase` will produce,
+    // leaving out a spill too small for the next phase's minimum purchase and
+    // stopping at a phase's maximum purchase.
+    // USD priced presales pass the budget in USD micro-units.
+    pub fn quote_tokens_for_lamports(&self, lamports: u64, now: i64) -> Result<u64> {
+        require!(self.get_current_phase().is_some(), PresaleError::InvalidPhase);
+
+        let mut tokens = 0u64;
+        let mut budget = lamports;
+        for phase in &self.phases[(self.current_phase - 1) as usize..] {
+            if !phase.is_open_at(now) {
+                break;
+            }
+            let take = phase.tokens_for_lamports(budget).ok_or(PresaleError::InvalidPrice)?
+                .min(phase.hardcap);
+            if tokens > 0 && !phase.meets_minimum_purchase(take) {
+                break;
+            }
+            let cost = phase.cost_for(take).ok_or(PresaleError::Overflow)?;
+            tokens = tokens.checked_add(take).ok_or(PresaleError::Overflow)?;
+            budget -= cost;
+            if take < phase.tokens_available {
+                break;
+            }
+        }
+
+        Ok(tokens)
+    }
+
+    // Records a fill against its phase and the presale totals, advancing to the
+    // next phase when this one sells out.
+    pub fn apply_fill(&mut self, fill: &PhaseFill) -> Result<()> {
+        let phase = &mut self.phases[(fill.phase_number - 1) as usize];
+        phase.tokens_sold = phase.tokens_sold.checked_add(fill.amount)
+            .ok_or(PresaleError::Overflow)?;
+        phase.tokens_available = phase.tokens_available.checked_sub(fill.amount)
+            .ok_or(PresaleError::InsufficientTokens)?;
+
+        self.total_tokens_sold = self.total_tokens_sold.checked_add(fill.amount)
+            .ok_or(PresaleError::Overflow)?;
+        self.remaining_tokens = self.remaining_tokens.checked_sub(fill.amount)
+            .ok_or(PresaleError::Overflow)?;
+
+        if self.update_phase_status() && self.current_phase != fill.phase_number {
+            msg!("Phase {} completed! Moving to Phase {}", fill.phase_number, self.current_phase);
+            msg!("New phase price: {}", self.phases[(self.current_phase - 1) as usize].price);
+        }
+        Ok(())
+    }
+
     pub fn deposit_tokens(&mut self, amount: u64) -> Result<()> {
         self.total_tokens_deposited = self.total_tokens_deposited.checked_add(amount)
             .ok_or(PresaleError::Overflow)?;
         Ok(())
     }
 
+    pub fn record_claimed(&mut self, amount: u64) -> Result<()> {
+        self.total_tokens_claimed = self.total_tokens_claimed.checked_add(amount)
+            .ok_or(PresaleError::Overflow)?;
+        Ok(())
+    }
+
+    // Tokens the presale account must keep: sold but not yet claimed, released
+    // but not yet returned or burned, and still for sale while sales can resume
+    pub fn outstanding_token_obligations(&self) -> u64 {
+        let unclaimed = self.total_tokens_sold.saturating_sub(self.total_tokens_claimed);
+        let for_sale = match self.state {
+            PresaleState::Draft | PresaleState::Live | PresaleState::Paused => self.remaining_tokens,
+            PresaleState::Finalized | PresaleState::Cancelled => 0,
+        };
+        unclaimed
+            .saturating_add(self.unsold_tokens)
+            .saturating_add(for_sale)
+    }
+
+    // Part of `vault_balance` the authority may withdraw without touching obligations
+    pub fn withdrawable_surplus(&self, vault_balance: u64) -> u64 {
+        vault_balance.saturating_sub(self.outstanding_token_obligations())
+    }
+
+    // Takes `amount` of the surplus out of `vault_balance`. Tokens sent to the
+    // presale account without `deposit_token` are taken first, only the rest
+    // comes out of `total_tokens_deposited`, which must still back every sale.
+    pub fn withdraw_surplus(&mut self, amount: u64, vault_balance: u64) -> Result<()> {
+        require!(amount <= self.withdrawable_surplus(vault_balance), PresaleError::ExceedsSurplus);
+        let deposited_in_vault = self.total_tokens_deposited.saturating_sub(self.total_tokens_claimed);
+        let untracked = vault_balance.saturating_sub(deposited_in_vault);
+        self.withdraw_tokens(amount.saturating_sub(untracked))
+    }
+
+    // Tokens sold can never exceed tokens deposited, otherwise claims would fail
+    pub fn is_deposit_sufficient(&self, amount: u64) -> bool {
+        match self.total_tokens_sold.checked_add(amount) {
+            Some(sold) => sold <= self.total_tokens_deposited,
+            None => false,
+        }
+    }
+
     pub fn withdraw_tokens(&mut self, amount: u64) -> Result<()> {
         require!(
             self.total_tokens_deposited >= amount,
@@ -114,10 +455,167 @@ impl PresaleInfo {
         Ok(())
     }
 
+    // Clears the released unsold tokens so they can be returned or burned. They
+    // leave the presale account, so they no longer back any purchase.
+    pub fn take_unsold_tokens(&mut self) -> Result<u64> {
+        let amount = self.unsold_tokens;
+        require!(amount > 0, PresaleError::NoUnsoldTokens);
+        self.withdraw_tokens(amount)?;
+        self.unsold_tokens = 0;
+        Ok(amount)
+    }
+
+    pub fn record_payment(&mut self, lamports: u64) -> Result<()> {
+        self.total_raised = self.total_raised.checked_add(lamports)
+            .ok_or(PresaleError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_refund(&mut self, tokens: u64, lamports: u64) -> Result<()> {
+        self.total_tokens_sold = self.total_tokens_sold.checked_sub(tokens)
+            .ok_or(PresaleError::Overflow)?;
+        self.total_raised = self.total_raised.checked_sub(lamports)
+            .ok_or(PresaleError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_token_payment(&mut self, mint_index: usize, amount: u64) -> Result<()> {
+        let raised = self.total_raised_tokens.get_mut(mint_index)
+            .ok_or(PresaleError::PaymentMintNotAccepted)?;
+        *raised = raised.checked_add(amount).ok_or(PresaleError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_token_refund(&mut self, mint_index: usize, amount: u64) -> Result<()> {
+        let raised = self.total_raised_tokens.get_mut(mint_index)
+            .ok_or(PresaleError::PaymentMintNotAccepted)?;
+        *raised = raised.checked_sub(amount).ok_or(PresaleError::Overflow)?;
+        Ok(())
+    }
+
+    // The softcap is in lamports, which SPL payments could never reach
+    pub fn is_valid_softcap(&self) -> bool {
+        self.softcap_lamports == 0 || self.payment_mints.is_empty()
+    }
+
+    // Measured in lamports, only SOL presales can have a softcap
+    pub fn is_softcap_reached(&self) -> bool {
+        self.total_raised >= self.softcap_lamports
+    }
+
+    // Sold out or past the final phase end time
+    pub fn is_sale_over(&self, now: i64) -> bool {
+        self.remaining_tokens == 0 || self.is_time_expired(now)
+    }
+
+    // Cancelled, or sales are over without reaching the softcap and the presale
+    // was never finalized
+    pub fn is_refund_available(&self, now: i64) -> bool {
+        match self.state {
+            PresaleState::Cancelled => true,
+            PresaleState::Live | PresaleState::Paused => {
+                !self.is_softcap_reached() && self.is_sale_over(now)
+            }
+            PresaleState::Draft | PresaleState::Finalized => false,
+        }
+    }
+
+    // Raised SOL stays in the vault to fund refunds until the presale is finalized
+    pub fn is_sol_withdrawable(&self) -> bool {
+        self.state == PresaleState::Finalized
+    }
+
+    // Closes sales for good once the softcap is reached, releasing whatever is
+    // still unsold for return or burn. A raise below its softcap must be
+    // cancelled so buyers can refund instead.
+    pub fn finalize(&mut self, now: i64) -> Result<()> {
+        require!(self.is_softcap_reached(), PresaleError::SoftcapNotReached);
+        self.transition_to(PresaleState::Finalized)?;
+        self.close_all_phases()?;
+        self.finalized_at = now;
+        Ok(())
+    }
+
+    // The TGE can only be moved while the presale is still running
+    pub fn set_claim_start_time(&mut self, claim_start_time: i64) -> Result<()> {
+        require!(
+            !matches!(self.state, PresaleState::Finalized | PresaleState::Cancelled),
+            PresaleError::PresaleEnded
+        );
+        require!(claim_start_time >= 0, PresaleError::InvalidAmount);
+        self.claim_start_time = claim_start_time;
+        Ok(())
+    }
+
+    pub fn is_claim_open(&self, now: i64) -> bool {
+        self.state == PresaleState::Finalized && now >= self.claim_start_time
+    }
+
+    // Vesting is measured from the TGE, or from finalization if that came later
+    pub fn vesting_start_time(&self) -> i64 {
+        self.claim_start_time.max(self.finalized_at)
+    }
+
+    // Tokens a user has bought in a phase that are unlocked at `now`
+    pub fn vested_amount(&self, user_info: &UserInfo, phase_index: usize, now: i64) -> u64 {
+        if !self.is_claim_open(now) {
+            return 0;
+        }
+        let elapsed = now - self.vesting_start_time();
+        self.phases[phase_index].vesting.vested_amount(user_info.phase_purchases[phase_index], elapsed)
+    }
+
+    // Vested tokens of a phase the user has not claimed yet
+    pub fn claimable_amount(&self, user_info: &UserInfo, phase_index: usize, now: i64) -> u64 {
+        self.vested_amount(user_info, phase_index, now)
+            .saturating_sub(user_info.phase_claimed[phase_index])
+    }
+
+    // (phase number, claimable amount) of every phase the user bought in that
+    // has vested tokens left to claim, and their total
+    pub fn plan_claim_all(&self, user_info: &UserInfo, now: i64) -> Result<(Vec<(u8, u64)>, u64)> {
+        let claims: Vec<(u8, u64)> = self.phases.iter()
+            .enumerate()
+            .filter(|(i, phase)| {
+                user_info.phase_purchases[*i] > 0 &&
+                (phase.status == PhaseStatus::Ended || phase.status == PhaseStatus::Active)
+            })
+            .map(|(i, phase)| (phase.phase_number, self.claimable_amount(user_info, i, now)))
+            .filter(|(_, amount)| *amount > 0)
+            .collect();
+        let total = claims.iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+            .ok_or(PresaleError::Overflow)?;
+        require!(total > 0, PresaleError::NothingVested);
+        Ok((claims, total))
+    }
+
+    pub fn transition_to(&mut self, next: PresaleState) -> Result<()> {
+        require!(
+            self.state.can_transition_to(next),
+            PresaleError::InvalidStateTransition
+        );
+        msg!("Presale state: {} -> {}", self.state.as_str(), next.as_str());
+        self.state = next;
+        Ok(())
+    }
+
+    // Ends every phase still open and releases all their unsold tokens, used
+    // when the presale is finalized before selling out.
+    pub fn close_all_phases(&mut self) -> Result<()> {
+        for phase_number in self.current_phase..=self.phase_count() {
+            self.phases[(phase_number - 1) as usize].status = PhaseStatus::Ended;
+            // Rolled over tokens flow down to the final phase, which releases them
+            self.release_phase_leftover(phase_number)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_within_wallet_cap(&self, wallet_total: u64) -> bool {
+        wallet_total <= self.max_token_amount_per_address
+    }
+
     pub fn can_participate(&self) -> bool {
-        self.is_initialized && 
-        self.is_active && 
-        !self.is_ended && 
-        !self.is_paused
+        self.state == PresaleState::Live
     }
 }
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use crate::errors::PresaleError;
+use crate::state::phase_info::PhaseFill;
+
+// Oracle used to price phases in USD. When a presale has one, `Phase::price` is
+// in USD micro-units (1_000_000 = $1) per whole token and SOL payments are
+// converted at the feed's SOL/USD price.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub struct PriceFeedConfig {
+    pub feed: Pubkey,             // SOL/USD price account (Pyth-style layout)
+    pub max_age: i64,             // Seconds after which a published price is stale
+    pub max_confidence_bps: u16,  // Widest accepted confidence interval, in basis points of the price
+}
+
+impl PriceFeedConfig {
+    pub const MAX_BPS: u16 = 10_000;
+
+    pub fn is_valid(&self) -> bool {
+        self.feed != Pubkey::default() &&
+        self.max_age > 0 &&
+        self.max_confidence_bps <= Self::MAX_BPS
+    }
+}
+
+// Aggregate SOL/USD price read from a feed account: `price * 10^expo` USD per SOL
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    // Offsets into a Pyth v2 price account
+    pub const MAGIC: u32 = 0xa1b2_c3d4;
+    pub const PRICE_ACCOUNT_TYPE: u32 = 3;
+    pub const STATUS_TRADING: u32 = 1;
+    const MAGIC_OFFSET: usize = 0;
+    const TYPE_OFFSET: usize = 8;
+    const EXPO_OFFSET: usize = 20;
+    const TIMESTAMP_OFFSET: usize = 96;
+    const AGG_PRICE_OFFSET: usize = 208;
+    const AGG_CONF_OFFSET: usize = 216;
+    const AGG_STATUS_OFFSET: usize = 224;
+    pub const MIN_LEN: usize = 240;
+
+    // Reads the aggregate price of a Pyth-style price account, rejecting
+    // anything that is not a trading, positive price
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require!(data.len() >= Self::MIN_LEN, PresaleError::InvalidPriceFeed);
+        require!(
+            read_u32(data, Self::MAGIC_OFFSET) == Self::MAGIC &&
+            read_u32(data, Self::TYPE_OFFSET) == Self::PRICE_ACCOUNT_TYPE &&
+            read_u32(data, Self::AGG_STATUS_OFFSET) == Self::STATUS_TRADING,
+            PresaleError::InvalidPriceFeed
+        );
+
+        let price = read_u64(data, Self::AGG_PRICE_OFFSET) as i64;
+        require!(price > 0, PresaleError::InvalidPriceFeed);
+        let expo = read_u32(data, Self::EXPO_OFFSET) as i32;
+        require!((-18..=0).contains(&expo), PresaleError::InvalidPriceFeed);
+
+        Ok(Self {
+            price: price as u64,
+            conf: read_u64(data, Self::AGG_CONF_OFFSET),
+            expo,
+            publish_time: read_u64(data, Self::TIMESTAMP_OFFSET) as i64,
+        })
+    }
+
+    // Rejects prices older than `max_age` or with a too wide confidence interval
+    pub fn validate(&self, config: &PriceFeedConfig, now: i64) -> Result<()> {
+        require!(
+            now.saturating_sub(self.publish_time) <= config.max_age,
+            PresaleError::StalePrice
+        );
+        require!(
+            self.conf as u128 * PriceFeedConfig::MAX_BPS as u128 <=
+                self.price as u128 * config.max_confidence_bps as u128,
+            PresaleError::PriceConfidenceTooWide
+        );
+        Ok(())
+    }
+
+    // 10^-expo, the feed's price units per USD
+    fn scale(&self) -> u128 {
+        10u128.pow(self.expo.unsigned_abs())
+    }
+
+    // Lamports worth `micro_usd`, rounded up in favour of the presale.
+    // lamports = micro_usd * 10^-6 USD / (price * 10^expo USD/SOL) * 10^9
+    pub fn usd_to_lamports(&self, micro_usd: u64) -> Option<u64> {
+        let lamports = (micro_usd as u128)
+            .checked_mul(1_000)?
+            .checked_mul(self.scale())?
+            .div_ceil(self.price as u128);
+        u64::try_from(lamports).ok()
+    }
+
+    // USD micro-units `lamports` are worth, rounded down
+    pub fn lamports_to_usd(&self, lamports: u64) -> Option<u64> {
+        let micro_usd = (lamports as u128)
+            .checked_mul(self.price as u128)?
+            .checked_div(1_000u128.checked_mul(self.scale())?)?;
+        u64::try_from(micro_usd).ok()
+    }
+
+    // Converts the USD cost of each fill to lamports. Converts the running total
+    // so the fills add up to exactly the converted cost of the whole purchase.
+    pub fn convert_fills(&self, fills: &mut [PhaseFill]) -> Result<()> {
+        let mut usd_total = 0u64;
+        let mut lamports_total = 0u64;
+        for fill in fills.iter_mut() {
+            usd_total = usd_total.checked_add(fill.cost).ok_or(PresaleError::Overflow)?;
+            let lamports = self.usd_to_lamports(usd_total).ok_or(PresaleError::Overflow)?;
+            fill.cost = lamports - lamports_total;
+            lamports_total = lamports;
+        }
+        Ok(())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
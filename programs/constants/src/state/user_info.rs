@@ -1,26 +1,64 @@
 use anchor_lang::prelude::*;
 use crate::errors::PresaleError;
+use crate::constants::presale_config::MAX_PAYMENT_MINTS;
 
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct UserInfo {
     // Total tokens bought by the user across all phases
     pub tokens_bought: u64,
-    // Array of tokens bought in each phase [phase1_amount, phase2_amount, ...]
-    pub phase_purchases: [u64; 6],
+    // Tokens bought in each phase [phase1_amount, phase2_amount, ...], one entry per presale phase
+    #[max_len(0)]
+    pub phase_purchases: Vec<u64>,
     // Last purchase timestamp
     pub last_purchase_time: i64,
-    // Array to track which phases have been claimed [phase1_claimed, phase2_claimed, ...]
-    pub phase_claims: [bool; 6],
+    // Tokens already claimed from each phase [phase1_claimed, phase2_claimed, ...], one entry per presale phase
+    #[max_len(0)]
+    pub phase_claimed: Vec<u64>,
     // The wallet address of the user
     pub wallet: Pubkey,
     // Total amount paid in lamports
     pub total_paid: u64,
+    // Total amount paid in each payment mint, in the order of `PresaleInfo::payment_mints`
+    #[max_len(MAX_PAYMENT_MINTS)]
+    pub token_paid: Vec<u64>,
+    // Nonce of the last KYC voucher used, vouchers must use a higher one
+    pub last_voucher_nonce: u64,
 }
 
 impl UserInfo {
+    // Seeds are [SEED_PREFIX, presale_info, wallet owning the allocation]
+    pub const SEED_PREFIX: &'static [u8] = b"user";
+
+    pub fn space(phase_count: usize) -> usize {
+        8 + Self::INIT_SPACE + phase_count * 2 * std::mem::size_of::<u64>()
+    }
+
+    // Address of the account holding `wallet`'s allocation in `presale`
+    pub fn address(presale: &Pubkey, wallet: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[Self::SEED_PREFIX, presale.as_ref(), wallet.as_ref()],
+            &crate::ID,
+        ).0
+    }
+
+    // Sets up a new account for `wallet`, the owner of the allocation
+    pub fn initialize(&mut self, wallet: Pubkey, phase_count: usize, payment_mint_count: usize) {
+        self.wallet = wallet;
+        self.tokens_bought = 0;
+        self.phase_purchases = vec![0; phase_count];
+        self.last_purchase_time = 0;
+        self.phase_claimed = vec![0; phase_count];
+        self.total_paid = 0;
+        self.token_paid = vec![0; payment_mint_count];
+    }
+
+    pub fn phase_count(&self) -> u8 {
+        self.phase_purchases.len() as u8
+    }
+
     pub fn record_purchase(&mut self, phase: u8, amount: u64, payment_amount: u64) -> Result<()> {
-        require!(phase > 0 && phase <= 6, PresaleError::InvalidPhase);
+        require!(phase > 0 && phase <= self.phase_count(), PresaleError::InvalidPhase);
         
         self.phase_purchases[(phase - 1) as usize] = self.phase_purchases[(phase - 1) as usize]
             .checked_add(amount)
@@ -39,8 +77,55 @@ impl UserInfo {
         Ok(())
     }
 
+    pub fn record_token_payment(&mut self, mint_index: usize, amount: u64) -> Result<()> {
+        let paid = self.token_paid.get_mut(mint_index)
+            .ok_or(PresaleError::PaymentMintNotAccepted)?;
+        *paid = paid.checked_add(amount).ok_or(PresaleError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn has_claimed_any(&self) -> bool {
+        self.phase_claimed.iter().any(|&claimed| claimed > 0)
+    }
+
+    pub fn record_claim(&mut self, phase: u8, amount: u64) -> Result<()> {
+        require!(phase > 0 && phase <= self.phase_count(), PresaleError::InvalidPhase);
+        let index = (phase - 1) as usize;
+        let claimed = self.phase_claimed[index].checked_add(amount)
+            .ok_or(PresaleError::Overflow)?;
+        require!(claimed <= self.phase_purchases[index], PresaleError::Overflow);
+        self.phase_claimed[index] = claimed;
+        Ok(())
+    }
+
+    // Anything left for `refund` to return or clear
+    pub fn has_refundable_purchase(&self) -> bool {
+        self.total_paid > 0 || self.tokens_bought > 0
+    }
+
+    // Zeroes the user's allocation and SOL payment, returning (tokens, lamports) to
+    // refund. Payments in SPL mints are refunded separately (`take_token_refund`).
+    pub fn clear_for_refund(&mut self) -> (u64, u64) {
+        let tokens = self.clear_allocation();
+        (tokens, std::mem::take(&mut self.total_paid))
+    }
+
+    // Zeroes the user's allocation, returning the tokens it held
+    pub fn clear_allocation(&mut self) -> u64 {
+        self.phase_purchases.iter_mut().for_each(|amount| *amount = 0);
+        std::mem::take(&mut self.tokens_bought)
+    }
+
+    // Zeroes what the user paid in the payment mint at `mint_index`, returning it
+    pub fn take_token_refund(&mut self, mint_index: usize) -> u64 {
+        match self.token_paid.get_mut(mint_index) {
+            Some(paid) => std::mem::take(paid),
+            None => 0,
+        }
+    }
+
     pub fn get_phase_amount(&self, phase: u8) -> Option<u64> {
-        if phase == 0 || phase > 6 {
+        if phase == 0 || phase > self.phase_count() {
             None
         } else {
             Some(self.phase_purchases[(phase - 1) as usize])
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::get_instruction_relative;
+use crate::errors::PresaleError;
+
+// Layout of an Ed25519 sigverify precompile instruction carrying one signature:
+// [num_signatures: u8, padding: u8, offsets (7 x u16), data...]
+pub const SIGNATURE_OFFSETS_START: usize = 2;
+pub const SIGNATURE_OFFSETS_SIZE: usize = 14;
+pub const PUBKEY_SIZE: usize = 32;
+pub const SIGNATURE_SIZE: usize = 64;
+// Instruction index meaning "this instruction" in the offsets
+pub const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+// Checks that `instruction` is an Ed25519 precompile instruction verifying a
+// single signature by `signer` over exactly `message`. The runtime has already
+// rejected the transaction if the signature itself is invalid.
+pub fn verify_ed25519_instruction(instruction: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    require_keys_eq!(instruction.program_id, ed25519_program::ID, PresaleError::InvalidVoucher);
+
+    let data = &instruction.data;
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE && data[0] == 1,
+        PresaleError::InvalidVoucher
+    );
+    let offset = |i: usize| {
+        let start = SIGNATURE_OFFSETS_START + i * 2;
+        u16::from_le_bytes([data[start], data[start + 1]])
+    };
+    let signature_instruction_index = offset(1);
+    let public_key_offset = offset(2) as usize;
+    let public_key_instruction_index = offset(3);
+    let message_offset = offset(4) as usize;
+    let message_size = offset(5) as usize;
+    let message_instruction_index = offset(6);
+
+    // Everything must be read from the precompile instruction itself, not from
+    // another instruction the signature check could be pointed at
+    require!(
+        signature_instruction_index == CURRENT_INSTRUCTION &&
+        public_key_instruction_index == CURRENT_INSTRUCTION &&
+        message_instruction_index == CURRENT_INSTRUCTION,
+        PresaleError::InvalidVoucher
+    );
+
+    let signed_pubkey = data.get(public_key_offset..public_key_offset + PUBKEY_SIZE)
+        .ok_or(PresaleError::InvalidVoucher)?;
+    let signed_message = data.get(message_offset..message_offset + message_size)
+        .ok_or(PresaleError::InvalidVoucher)?;
+    require!(
+        signed_pubkey == signer.as_ref() && signed_message == message,
+        PresaleError::InvalidVoucher
+    );
+    Ok(())
+}
+
+// Verifies the instruction right before the current one in the transaction is
+// an Ed25519 signature by `signer` over `message`
+pub fn verify_preceding_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let instruction = get_instruction_relative(-1, instructions_sysvar)
+        .map_err(|_| error!(PresaleError::InvalidVoucher))?;
+    verify_ed25519_instruction(&instruction, signer, message)
+}
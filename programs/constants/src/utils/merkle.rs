@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+// Allowlist Merkle trees. A leaf commits to a wallet and the most it may buy in
+// the phase, parent nodes hash their two children in sorted order so proofs do
+// not need left/right flags. Leaves and nodes use different prefixes so a node
+// can never be passed off as a leaf.
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+pub fn allowlist_leaf(wallet: &Pubkey, max_allocation: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, wallet.as_ref(), &max_allocation.to_le_bytes()]).to_bytes()
+}
+
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
+
+// Builds trees and proofs off-chain, see `examples/allowlist.rs`
+pub struct MerkleTree {
+    // levels[0] holds the leaves, the last level holds the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    // An odd node at the end of a level is promoted to the next level unchanged
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels.last().unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
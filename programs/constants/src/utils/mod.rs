@@ -0,0 +1,5 @@
+pub mod merkle;
+pub mod ed25519;
+
+pub use merkle::*;
+pub use ed25519::*;
\ No newline at end of file
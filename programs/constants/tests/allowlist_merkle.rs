@@ -0,0 +1,47 @@
+use anchor_lang::prelude::Pubkey;
+use constants::state::Phase;
+use constants::utils::merkle::{allowlist_leaf, verify_proof, MerkleTree};
+
+fn allowlist(size: usize) -> (Vec<(Pubkey, u64)>, MerkleTree) {
+    let entries: Vec<(Pubkey, u64)> = (0..size)
+        .map(|i| (Pubkey::new_unique(), 1_000 * (i as u64 + 1)))
+        .collect();
+    let leaves = entries.iter().map(|(wallet, cap)| allowlist_leaf(wallet, *cap)).collect();
+    (entries, MerkleTree::new(leaves))
+}
+
+#[test]
+fn every_entry_proves_membership() {
+    // Odd sizes exercise nodes promoted without a sibling
+    for size in [1, 2, 3, 5, 8, 13] {
+        let (entries, tree) = allowlist(size);
+        for (i, (wallet, cap)) in entries.iter().enumerate() {
+            assert!(verify_proof(&tree.proof(i), &tree.root(), allowlist_leaf(wallet, *cap)));
+        }
+    }
+}
+
+#[test]
+fn wrong_wallet_allocation_or_proof_is_rejected() {
+    let (entries, tree) = allowlist(5);
+    let (wallet, cap) = entries[2];
+    let proof = tree.proof(2);
+
+    assert!(!verify_proof(&proof, &tree.root(), allowlist_leaf(&Pubkey::new_unique(), cap)));
+    // Claiming a larger allocation than the one committed to
+    assert!(!verify_proof(&proof, &tree.root(), allowlist_leaf(&wallet, cap + 1)));
+    assert!(!verify_proof(&tree.proof(3), &tree.root(), allowlist_leaf(&wallet, cap)));
+}
+
+#[test]
+fn phase_without_root_is_open_to_all() {
+    let (entries, tree) = allowlist(4);
+    let (wallet, cap) = entries[1];
+
+    let open = Phase::default();
+    assert!(open.is_allowlisted(&Pubkey::new_unique(), 0, &[]));
+
+    let restricted = Phase { allowlist_root: Some(tree.root()), ..Default::default() };
+    assert!(restricted.is_allowlisted(&wallet, cap, &tree.proof(1)));
+    assert!(!restricted.is_allowlisted(&Pubkey::new_unique(), cap, &tree.proof(1)));
+}
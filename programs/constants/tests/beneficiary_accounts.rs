@@ -0,0 +1,30 @@
+use anchor_lang::prelude::Pubkey;
+use constants::state::UserInfo;
+
+#[test]
+fn user_account_is_derived_from_the_beneficiary() {
+    let presale = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let beneficiary = Pubkey::new_unique();
+
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"user", presale.as_ref(), beneficiary.as_ref()],
+        &constants::ID,
+    );
+    assert_eq!(UserInfo::address(&presale, &beneficiary), expected);
+    assert_ne!(UserInfo::address(&presale, &beneficiary), UserInfo::address(&presale, &payer));
+    // Allocations are per presale
+    assert_ne!(UserInfo::address(&presale, &beneficiary), UserInfo::address(&Pubkey::new_unique(), &beneficiary));
+}
+
+#[test]
+fn new_user_account_belongs_to_the_beneficiary() {
+    let beneficiary = Pubkey::new_unique();
+    let mut user = UserInfo::default();
+    user.initialize(beneficiary, 3, 1);
+
+    assert_eq!(user.wallet, beneficiary);
+    assert_eq!(user.phase_purchases, vec![0; 3]);
+    assert_eq!(user.phase_claimed, vec![0; 3]);
+    assert_eq!(user.token_paid, vec![0]);
+}
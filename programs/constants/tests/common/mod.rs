@@ -0,0 +1,44 @@
+// Fixtures shared by the integration tests, each test binary uses a subset
+#![allow(dead_code)]
+
+use constants::constants::DECIMALS_MULTIPLIER;
+use constants::state::{Phase, PhaseStatus, PresaleInfo};
+
+pub const TOKEN: u64 = DECIMALS_MULTIPLIER;
+
+// Unsold phase of `amount` tokens at `price` per token, minimum purchase of one
+// token. Phase 1 starts active, the others upcoming, as `create_presale` sets them.
+pub fn phase(phase_number: u8, amount: u64, price: u64) -> Phase {
+    Phase {
+        phase_number,
+        amount,
+        price,
+        tokens_available: amount,
+        status: if phase_number == 1 { PhaseStatus::Active } else { PhaseStatus::Upcoming },
+        softcap: TOKEN,
+        hardcap: amount,
+        ..Default::default()
+    }
+}
+
+// Draft presale selling `phases` with the supply matching their amounts
+pub fn presale_with(phases: Vec<Phase>) -> PresaleInfo {
+    let total = phases.iter().map(|p| p.amount).sum();
+    PresaleInfo {
+        total_token_supply: total,
+        remaining_tokens: total,
+        current_phase: 1,
+        phases,
+        ..Default::default()
+    }
+}
+
+// Same as `presale_with`, one phase per (amount, price)
+pub fn presale(phases: &[(u64, u64)]) -> PresaleInfo {
+    presale_with(
+        phases.iter()
+            .enumerate()
+            .map(|(i, &(amount, price))| phase((i + 1) as u8, amount, price))
+            .collect(),
+    )
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::TOKEN;
+use constants::state::{OraclePrice, PhaseFill, PriceFeedConfig};
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+const NOW: i64 = 1_700_000_000;
+
+// Writes a Pyth v2 style SOL/USD price account with expo -8
+fn mock_feed(price: i64, conf: u64, publish_time: i64) -> Vec<u8> {
+    let mut data = vec![0u8; OraclePrice::MIN_LEN];
+    data[0..4].copy_from_slice(&OraclePrice::MAGIC.to_le_bytes());
+    data[8..12].copy_from_slice(&OraclePrice::PRICE_ACCOUNT_TYPE.to_le_bytes());
+    data[20..24].copy_from_slice(&(-8i32).to_le_bytes());
+    data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&OraclePrice::STATUS_TRADING.to_le_bytes());
+    data
+}
+
+fn config() -> PriceFeedConfig {
+    PriceFeedConfig {
+        feed: Pubkey::new_unique(),
+        max_age: 60,
+        max_confidence_bps: 100,
+    }
+}
+
+#[test]
+fn parses_mock_feed() {
+    let price = OraclePrice::parse(&mock_feed(15_000_000_000, 1_000_000, NOW)).unwrap();
+    assert_eq!(price, OraclePrice { price: 15_000_000_000, conf: 1_000_000, expo: -8, publish_time: NOW });
+}
+
+#[test]
+fn rejects_malformed_feeds() {
+    assert!(OraclePrice::parse(&[0u8; 100]).is_err());
+
+    let mut wrong_magic = mock_feed(15_000_000_000, 0, NOW);
+    wrong_magic[0] = 0;
+    assert!(OraclePrice::parse(&wrong_magic).is_err());
+
+    let mut halted = mock_feed(15_000_000_000, 0, NOW);
+    halted[224..228].copy_from_slice(&2u32.to_le_bytes());
+    assert!(OraclePrice::parse(&halted).is_err());
+
+    assert!(OraclePrice::parse(&mock_feed(0, 0, NOW)).is_err());
+    assert!(OraclePrice::parse(&mock_feed(-1, 0, NOW)).is_err());
+}
+
+#[test]
+fn rejects_stale_and_uncertain_prices() {
+    let config = config();
+    let fresh = OraclePrice::parse(&mock_feed(15_000_000_000, 150_000_000, NOW)).unwrap();
+    assert!(fresh.validate(&config, NOW + 60).is_ok());
+    assert!(fresh.validate(&config, NOW + 61).is_err());
+
+    // 1% confidence is the limit
+    let uncertain = OraclePrice::parse(&mock_feed(15_000_000_000, 150_000_001, NOW)).unwrap();
+    assert!(uncertain.validate(&config, NOW).is_err());
+}
+
+#[test]
+fn converts_usd_to_lamports_at_the_feed_price() {
+    let price = OraclePrice::parse(&mock_feed(15_000_000_000, 0, NOW)).unwrap();
+
+    // $150 buys one SOL, $1 buys 1/150 SOL rounded up
+    assert_eq!(price.usd_to_lamports(150_000_000), Some(LAMPORTS_PER_SOL));
+    assert_eq!(price.usd_to_lamports(1_000_000), Some(6_666_667));
+    assert_eq!(price.lamports_to_usd(LAMPORTS_PER_SOL), Some(150_000_000));
+    assert_eq!(price.lamports_to_usd(6_666_667), Some(1_000_000));
+}
+
+#[test]
+fn converted_fills_add_up_to_the_converted_total() {
+    let price = OraclePrice::parse(&mock_feed(15_000_000_000, 0, NOW)).unwrap();
+    // $0.01 and $0.02 per token
+    let mut fills = vec![
+        PhaseFill { phase_number: 1, amount: 3 * TOKEN, cost: 30_000 },
+        PhaseFill { phase_number: 2, amount: 7 * TOKEN, cost: 140_000 },
+    ];
+    price.convert_fills(&mut fills).unwrap();
+
+    let total: u64 = fills.iter().map(|fill| fill.cost).sum();
+    assert_eq!(total, price.usd_to_lamports(170_000).unwrap());
+    assert_eq!(fills[0].cost, price.usd_to_lamports(30_000).unwrap());
+}
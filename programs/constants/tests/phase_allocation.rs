@@ -0,0 +1,75 @@
+mod common;
+
+use common::{phase, presale_with, TOKEN};
+use constants::constants::MAX_PHASES;
+use constants::state::{Phase, PresaleInfo, UserInfo};
+
+fn presale(phases: Vec<Phase>, total_token_supply: u64) -> PresaleInfo {
+    PresaleInfo {
+        total_token_supply,
+        remaining_tokens: total_token_supply,
+        ..presale_with(phases)
+    }
+}
+
+#[test]
+fn schedule_matching_the_supply_is_valid() {
+    let presale = presale(vec![phase(1, 100 * TOKEN, 1_000), phase(2, 100 * TOKEN, 2_000)], 200 * TOKEN);
+    assert!(presale.validate_phase_allocation());
+}
+
+#[test]
+fn empty_schedule_is_rejected() {
+    assert!(!presale(Vec::new(), 0).validate_phase_allocation());
+    assert!(!presale(Vec::new(), 100 * TOKEN).validate_phase_allocation());
+}
+
+#[test]
+fn zero_price_or_amount_is_rejected() {
+    let zero_price = presale(vec![phase(1, 100 * TOKEN, 1_000), phase(2, 100 * TOKEN, 0)], 200 * TOKEN);
+    assert!(!zero_price.validate_phase_allocation());
+
+    let zero_amount = presale(vec![phase(1, 100 * TOKEN, 1_000), phase(2, 0, 2_000)], 100 * TOKEN);
+    assert!(!zero_amount.validate_phase_allocation());
+}
+
+#[test]
+fn allocation_must_match_the_total_supply() {
+    let phases = vec![phase(1, 100 * TOKEN, 1_000), phase(2, 100 * TOKEN, 2_000)];
+    assert!(!presale(phases.clone(), 200 * TOKEN - 1).validate_phase_allocation());
+    assert!(!presale(phases, 200 * TOKEN + 1).validate_phase_allocation());
+}
+
+#[test]
+fn account_space_grows_with_the_phase_count() {
+    assert!(PresaleInfo::space(2) > PresaleInfo::space(1));
+    assert_eq!(
+        PresaleInfo::space(MAX_PHASES) - PresaleInfo::space(MAX_PHASES - 1),
+        PresaleInfo::space(2) - PresaleInfo::space(1)
+    );
+    assert!(UserInfo::space(2) > UserInfo::space(1));
+}
+
+#[test]
+fn phase_count_is_bounded() {
+    assert!(!PresaleInfo::is_valid_phase_count(0));
+    assert!(PresaleInfo::is_valid_phase_count(1));
+    assert!(PresaleInfo::is_valid_phase_count(MAX_PHASES));
+    assert!(!PresaleInfo::is_valid_phase_count(MAX_PHASES + 1));
+
+    let phases: Vec<Phase> = (1..=MAX_PHASES as u8 + 1).map(|n| phase(n, 10 * TOKEN, 1_000)).collect();
+    let supply = phases.iter().map(|p| p.amount).sum();
+    assert!(!presale(phases, supply).validate_phase_allocation());
+}
+
+#[test]
+fn user_phase_amounts_cover_every_phase() {
+    let user = UserInfo {
+        phase_purchases: (1..=MAX_PHASES as u64).map(|n| n * TOKEN).collect(),
+        ..Default::default()
+    };
+    assert_eq!(user.get_phase_amount(0), None);
+    assert_eq!(user.get_phase_amount(1), Some(TOKEN));
+    assert_eq!(user.get_phase_amount(MAX_PHASES as u8), Some(MAX_PHASES as u64 * TOKEN));
+    assert_eq!(user.get_phase_amount(MAX_PHASES as u8 + 1), None);
+}
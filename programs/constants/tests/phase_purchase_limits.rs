@@ -0,0 +1,131 @@
+mod common;
+
+use common::{phase, presale_with, TOKEN};
+use constants::state::{Phase, PresaleInfo};
+
+const MIN_PURCHASE: u64 = 100 * TOKEN;
+
+fn phase_with_minimum(phase_number: u8, amount: u64, tokens_sold: u64) -> Phase {
+    Phase {
+        tokens_sold,
+        tokens_available: amount - tokens_sold,
+        softcap: MIN_PURCHASE,
+        ..phase(phase_number, amount, 40_000)
+    }
+}
+
+fn active_phase(amount: u64, tokens_sold: u64) -> Phase {
+    phase_with_minimum(1, amount, tokens_sold)
+}
+
+#[test]
+fn purchase_at_minimum_is_accepted() {
+    let phase = active_phase(50_000 * TOKEN, 0);
+    assert!(phase.meets_minimum_purchase(MIN_PURCHASE));
+    assert!(phase.meets_minimum_purchase(MIN_PURCHASE + 1));
+}
+
+#[test]
+fn purchase_below_minimum_is_rejected() {
+    let phase = active_phase(50_000 * TOKEN, 0);
+    assert!(!phase.meets_minimum_purchase(MIN_PURCHASE - 1));
+    assert!(!phase.meets_minimum_purchase(1));
+    assert!(!phase.meets_minimum_purchase(0));
+}
+
+#[test]
+fn final_remainder_below_minimum_can_be_bought_out() {
+    let amount = 50_000 * TOKEN;
+    let remainder = 40 * TOKEN;
+    let phase = active_phase(amount, amount - remainder);
+
+    assert!(phase.meets_minimum_purchase(remainder));
+    // A partial buy of the remainder is not
+    assert!(!phase.meets_minimum_purchase(remainder - 1));
+}
+
+fn presale_with_remainder(remainder: u64) -> PresaleInfo {
+    let amount = 50_000 * TOKEN;
+    PresaleInfo {
+        remaining_tokens: amount + remainder,
+        ..presale_with(vec![
+            phase_with_minimum(1, amount, amount - remainder),
+            phase_with_minimum(2, amount, 0),
+        ])
+    }
+}
+
+#[test]
+fn spill_into_next_phase_must_meet_its_minimum() {
+    let remainder = 40 * TOKEN;
+    let presale = presale_with_remainder(remainder);
+
+    // Buying out the remainder is exempt, the spilled part is not
+    assert!(presale.plan_purchase(remainder, 0).is_ok());
+    assert!(presale.plan_purchase(remainder + 1, 0).is_err());
+    assert!(presale.plan_purchase(remainder + MIN_PURCHASE - 1, 0).is_err());
+    let fills = presale.plan_purchase(remainder + MIN_PURCHASE, 0).unwrap();
+    assert_eq!(fills[1].amount, MIN_PURCHASE);
+}
+
+#[test]
+fn sol_quote_leaves_out_a_spill_below_the_next_minimum() {
+    let remainder = 40 * TOKEN;
+    let presale = presale_with_remainder(remainder);
+    let remainder_cost = presale.phases[0].cost_for(remainder).unwrap();
+
+    let spill_cost = presale.phases[1].cost_for(MIN_PURCHASE / 2).unwrap();
+    assert_eq!(presale.quote_tokens_for_lamports(remainder_cost + spill_cost, 0).unwrap(), remainder);
+
+    let spill_cost = presale.phases[1].cost_for(MIN_PURCHASE).unwrap();
+    let tokens = presale.quote_tokens_for_lamports(remainder_cost + spill_cost, 0).unwrap();
+    assert_eq!(tokens, remainder + MIN_PURCHASE);
+    assert!(presale.plan_purchase(tokens, 0).is_ok());
+}
+
+#[test]
+fn zero_minimum_accepts_any_non_zero_amount() {
+    let mut phase = active_phase(50_000 * TOKEN, 0);
+    phase.softcap = 0;
+    assert!(phase.meets_minimum_purchase(1));
+    assert!(!phase.meets_minimum_purchase(0));
+}
+
+#[test]
+fn per_phase_wallet_cap_is_optional() {
+    let mut phase = active_phase(50_000 * TOKEN, 0);
+    assert!(phase.is_within_wallet_cap(u64::MAX));
+
+    phase.max_per_wallet = Some(5_000 * TOKEN);
+    assert!(phase.is_within_wallet_cap(5_000 * TOKEN));
+    assert!(!phase.is_within_wallet_cap(5_000 * TOKEN + 1));
+}
+
+#[test]
+fn purchases_are_limited_to_deposited_tokens() {
+    let presale = PresaleInfo {
+        total_tokens_deposited: 1_000 * TOKEN,
+        total_tokens_sold: 800 * TOKEN,
+        ..Default::default()
+    };
+    assert!(presale.is_deposit_sufficient(200 * TOKEN));
+    assert!(!presale.is_deposit_sufficient(200 * TOKEN + 1));
+    assert!(!presale.is_deposit_sufficient(u64::MAX));
+
+    let unfunded = PresaleInfo::default();
+    assert!(!unfunded.is_deposit_sufficient(1));
+}
+
+#[test]
+fn fill_above_the_phase_maximum_is_rejected() {
+    let max_purchase = 1_000 * TOKEN;
+    let mut presale = presale_with(vec![active_phase(50_000 * TOKEN, 0)]);
+    presale.phases[0].hardcap = max_purchase;
+
+    assert!(presale.plan_purchase(max_purchase, 0).is_ok());
+    assert!(presale.plan_purchase(max_purchase + 1, 0).is_err());
+
+    // A SOL budget worth more is quoted down to the maximum
+    let budget = presale.phases[0].cost_for(2 * max_purchase).unwrap();
+    assert_eq!(presale.quote_tokens_for_lamports(budget, 0).unwrap(), max_purchase);
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use common::{presale, TOKEN};
+use constants::state::{PhaseFill, PhaseStatus, PresaleInfo, UnsoldTokenPolicy};
+
+#[test]
+fn purchase_within_phase_is_a_single_fill() {
+    let presale = presale(&[(100 * TOKEN, 1_000), (100 * TOKEN, 2_000)]);
+    let fills = presale.plan_purchase(40 * TOKEN, 0).unwrap();
+    assert_eq!(fills, vec![PhaseFill { phase_number: 1, amount: 40 * TOKEN, cost: 40_000 }]);
+}
+
+#[test]
+fn purchase_spills_into_next_phase_at_its_price() {
+    let mut presale = presale(&[(100 * TOKEN, 1_000), (100 * TOKEN, 2_000), (100 * TOKEN, 3_000)]);
+    presale.apply_fill(&PhaseFill { phase_number: 1, amount: 70 * TOKEN, cost: 70_000 }).unwrap();
+
+    let fills = presale.plan_purchase(150 * TOKEN, 0).unwrap();
+    assert_eq!(fills, vec![
+        PhaseFill { phase_number: 1, amount: 30 * TOKEN, cost: 30_000 },
+        PhaseFill { phase_number: 2, amount: 100 * TOKEN, cost: 200_000 },
+        PhaseFill { phase_number: 3, amount: 20 * TOKEN, cost: 60_000 },
+    ]);
+
+    for fill in &fills {
+        presale.apply_fill(fill).unwrap();
+    }
+    assert_eq!(presale.current_phase, 3);
+    assert_eq!(presale.phases[0].status, PhaseStatus::Ended);
+    assert_eq!(presale.phases[1].status, PhaseStatus::Ended);
+    assert_eq!(presale.phases[2].status, PhaseStatus::Active);
+    assert_eq!(presale.phases[2].tokens_available, 80 * TOKEN);
+    assert_eq!(presale.total_tokens_sold, 220 * TOKEN);
+    assert_eq!(presale.remaining_tokens, 80 * TOKEN);
+}
+
+#[test]
+fn purchase_beyond_last_phase_is_rejected() {
+    let presale = presale(&[(100 * TOKEN, 1_000), (100 * TOKEN, 2_000)]);
+    assert!(presale.plan_purchase(200 * TOKEN + 1, 0).is_err());
+}
+
+#[test]
+fn selling_out_final_phase_ends_it_in_place() {
+    let mut presale = presale(&[(100 * TOKEN, 1_000)]);
+    presale.apply_fill(&PhaseFill { phase_number: 1, amount: 100 * TOKEN, cost: 100_000 }).unwrap();
+    assert_eq!(presale.current_phase, 1);
+    assert_eq!(presale.phases[0].status, PhaseStatus::Ended);
+    assert_eq!(presale.remaining_tokens, 0);
+}
+
+#[test]
+fn sol_quote_spills_and_matches_planned_cost() {
+    let presale = presale(&[(100 * TOKEN, 1_000), (100 * TOKEN, 2_000)]);
+    // 100 tokens at 1_000 plus 25 tokens at 2_000
+    let lamports = 150_000;
+    let tokens = presale.quote_tokens_for_lamports(lamports, 0).unwrap();
+    assert_eq!(tokens, 125 * TOKEN);
+
+    let cost: u64 = presale.plan_purchase(tokens, 0).unwrap().iter().map(|f| f.cost).sum();
+    assert_eq!(cost, lamports);
+}
+
+#[test]
+fn phase_closes_on_schedule_without_selling_out() {
+    let mut presale = presale(&[(100 * TOKEN, 1_000), (100 * TOKEN, 2_000)]);
+    presale.phases[0].end_time = Some(1_000);
+    presale.phases[1].start_time = Some(1_500);
+
+    assert!(presale.sync_phases(999).is_empty());
+    assert_eq!(presale.sync_phases(1_000), vec![1]);
+    assert_eq!(presale.current_phase, 2);
+    assert_eq!(presale.phases[0].status, PhaseStatus::Ended);
+
+    // Next phase has not opened yet
+    assert!(presale.plan_purchase(10 * TOKEN, 1_200).is_err());
+    assert_eq!(presale.plan_purchase(10 * TOKEN, 1_500).unwrap()[0].phase_number, 2);
+}
+
+#[test]
+fn purchase_after_final_phase_expiry_is_rejected() {
+    let mut presale = presale(&[(100 * TOKEN, 1_000)]);
+    presale.phases[0].end_time = Some(1_000);
+
+    assert_eq!(presale.sync_phases(2_000), vec![1]);
+    assert!(presale.is_time_expired(2_000));
+    assert!(presale.plan_purchase(10 * TOKEN, 2_000).is_err());
+}
+
+fn available_sum(presale: &PresaleInfo) -> u64 {
+    presale.phases.iter().map(|p| p.tokens_available).sum()
+}
+
+#[test]
+fn unsold_tokens_roll_over_into_next_phase() {
+    let mut presale = presale(&[(100 * TOKEN, 1_000), (100 * TOKEN, 2_000)]);
+    presale.phases[0].end_time = Some(1_000);
+    presale.apply_fill(&PhaseFill { phase_number: 1, amount: 60 * TOKEN, cost: 60_000 }).unwrap();
+
+    presale.close_expired_phases(1_000).unwrap();
+    assert_eq!(presale.phases[0].tokens_available, 0);
+    assert_eq!(presale.phases[0].amount, 60 * TOKEN);
+    assert_eq!(presale.phases[1].amount, 140 * TOKEN);
+    assert_eq!(presale.phases[1].tokens_available, 140 * TOKEN);
+    assert_eq!(presale.remaining_tokens, available_sum(&presale));
+    assert_eq!(presale.unsold_tokens, 0);
+}
+
+#[test]
+fn unsold_tokens_are_released_for_return_or_burn() {
+    for policy in [UnsoldTokenPolicy::ReturnToAuthority, UnsoldTokenPolicy::Burn] {
+        let mut presale = presale(&[(100 * TOKEN, 1_000), (100 * TOKEN, 2_000)]);
+        presale.unsold_token_policy = policy;
+        presale.phases[0].end_time = Some(1_000);
+        presale.apply_fill(&PhaseFill { phase_number: 1, amount: 60 * TOKEN, cost: 60_000 }).unwrap();
+
+        presale.close_expired_phases(1_000).unwrap();
+        assert_eq!(presale.phases[1].tokens_available, 100 * TOKEN);
+        assert_eq!(presale.unsold_tokens, 40 * TOKEN);
+        assert_eq!(presale.remaining_tokens, 100 * TOKEN);
+        assert_eq!(presale.remaining_tokens, available_sum(&presale));
+    }
+}
+
+#[test]
+fn released_tokens_no_longer_back_purchases() {
+    let mut presale = presale(&[(500 * TOKEN, 1_000), (500 * TOKEN, 2_000)]);
+    presale.unsold_token_policy = UnsoldTokenPolicy::ReturnToAuthority;
+    presale.phases[0].end_time = Some(1_000);
+    presale.deposit_tokens(500 * TOKEN).unwrap();
+
+    presale.close_expired_phases(1_000).unwrap();
+    assert!(presale.is_deposit_sufficient(500 * TOKEN));
+
+    assert_eq!(presale.take_unsold_tokens().unwrap(), 500 * TOKEN);
+    assert_eq!(presale.total_tokens_deposited, 0);
+    assert_eq!(presale.unsold_tokens, 0);
+    // Phase 2 still has tokens for sale but none of them are in the presale account
+    assert!(presale.plan_purchase(10 * TOKEN, 1_000).is_ok());
+    assert!(!presale.is_deposit_sufficient(10 * TOKEN));
+    assert!(presale.take_unsold_tokens().is_err());
+}
+
+#[test]
+fn final_phase_leftover_is_released_even_when_rolling_over() {
+    let mut presale = presale(&[(100 * TOKEN, 1_000)]);
+    presale.phases[0].end_time = Some(1_000);
+
+    presale.close_expired_phases(1_000).unwrap();
+    assert_eq!(presale.unsold_tokens, 100 * TOKEN);
+    assert_eq!(presale.remaining_tokens, 0);
+}
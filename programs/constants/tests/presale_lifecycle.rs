@@ -0,0 +1,36 @@
+use constants::state::PresaleState::{self, *};
+
+const ALL: [PresaleState; 5] = [Draft, Live, Paused, Finalized, Cancelled];
+
+#[test]
+fn legal_transitions() {
+    let legal = [
+        (Draft, Live),
+        (Live, Paused),
+        (Paused, Live),
+        (Live, Finalized),
+        (Paused, Finalized),
+        (Draft, Cancelled),
+        (Live, Cancelled),
+        (Paused, Cancelled),
+    ];
+    for from in ALL {
+        for to in ALL {
+            assert_eq!(
+                from.can_transition_to(to),
+                legal.contains(&(from, to)),
+                "{} -> {}",
+                from.as_str(),
+                to.as_str()
+            );
+        }
+    }
+}
+
+#[test]
+fn terminal_states_have_no_way_out() {
+    for to in ALL {
+        assert!(!Finalized.can_transition_to(to));
+        assert!(!Cancelled.can_transition_to(to));
+    }
+}
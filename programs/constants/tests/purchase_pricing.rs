@@ -0,0 +1,68 @@
+mod common;
+
+use common::{phase, TOKEN};
+use constants::state::Phase;
+
+fn phase_with_price(price: u64) -> Phase {
+    phase(1, 1_000_000 * TOKEN, price)
+}
+
+#[test]
+fn whole_tokens_cost_exactly_price() {
+    let phase = phase_with_price(40_000);
+    assert_eq!(phase.cost_for(TOKEN), Some(40_000));
+    assert_eq!(phase.cost_for(250 * TOKEN), Some(10_000_000));
+}
+
+#[test]
+fn fractional_tokens_are_charged() {
+    let phase = phase_with_price(40_000);
+    // 0.99 tokens used to truncate to 0 whole tokens and cost nothing
+    assert_eq!(phase.cost_for(990_000_000), Some(39_600));
+    // 1.5 tokens
+    assert_eq!(phase.cost_for(1_500_000_000), Some(60_000));
+}
+
+#[test]
+fn partial_lamports_round_up_in_favour_of_presale() {
+    let phase = phase_with_price(8_570);
+    // 0.0000001 tokens * 8_570 lamports = 0.000857 lamports -> 1 lamport
+    assert_eq!(phase.cost_for(100), Some(1));
+    // 1.0000000001 tokens -> 8_570.000008570 lamports -> 8_571
+    assert_eq!(phase.cost_for(TOKEN + 1), Some(8_571));
+}
+
+#[test]
+fn no_zero_cost_purchases() {
+    for price in [1, 7, 8_570, 40_000] {
+        let phase = phase_with_price(price);
+        for amount in [1, 2, 999, TOKEN - 1, TOKEN] {
+            let cost = phase.cost_for(amount).unwrap();
+            assert!(cost > 0, "amount {amount} at price {price} cost nothing");
+        }
+    }
+}
+
+#[test]
+fn cost_overflowing_u64_is_rejected() {
+    let phase = phase_with_price(u64::MAX);
+    assert_eq!(phase.cost_for(u64::MAX), None);
+}
+
+#[test]
+fn sol_quote_never_costs_more_than_lamports_in() {
+    for price in [1, 7, 8_570, 40_000] {
+        let phase = phase_with_price(price);
+        for lamports in [1, 999, 40_000, 1_234_567_891] {
+            let tokens = phase.tokens_for_lamports(lamports).unwrap();
+            assert!(phase.cost_for(tokens).unwrap() <= lamports);
+        }
+    }
+}
+
+#[test]
+fn sol_quote_is_capped_at_phase_availability() {
+    let mut phase = phase_with_price(40_000);
+    phase.tokens_available = 10 * TOKEN;
+    assert_eq!(phase.tokens_for_lamports(u64::MAX), Some(10 * TOKEN));
+}
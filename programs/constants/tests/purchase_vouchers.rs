@@ -0,0 +1,92 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use constants::instructions::PurchaseVoucher;
+use constants::utils::ed25519::{
+    verify_ed25519_instruction, CURRENT_INSTRUCTION, PUBKEY_SIZE, SIGNATURE_OFFSETS_SIZE,
+    SIGNATURE_OFFSETS_START, SIGNATURE_SIZE,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+// Same layout as the Ed25519 precompile instruction the client sends, with a
+// dummy signature: the runtime, not the program, checks the signature itself
+fn ed25519_instruction(signer: &Pubkey, message: &[u8], index: u16) -> Instruction {
+    let public_key_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE;
+    let signature_offset = public_key_offset + PUBKEY_SIZE;
+    let message_offset = signature_offset + SIGNATURE_SIZE;
+
+    let mut data = vec![1u8, 0];
+    for value in [
+        signature_offset as u16,
+        index,
+        public_key_offset as u16,
+        index,
+        message_offset as u16,
+        message.len() as u16,
+        index,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(&[7u8; SIGNATURE_SIZE]);
+    data.extend_from_slice(message);
+
+    Instruction { program_id: ed25519_program::ID, accounts: vec![], data }
+}
+
+fn voucher(buyer: Pubkey) -> PurchaseVoucher {
+    PurchaseVoucher { buyer, max_amount: 1_000, expiry: NOW + 300, nonce: 5 }
+}
+
+#[test]
+fn accepts_signature_by_the_kyc_signer_over_the_voucher() {
+    let (presale, signer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let message = voucher(Pubkey::new_unique()).message(&presale);
+    assert_eq!(message.len(), PurchaseVoucher::MESSAGE_LEN);
+
+    let instruction = ed25519_instruction(&signer, &message, CURRENT_INSTRUCTION);
+    assert!(verify_ed25519_instruction(&instruction, &signer, &message).is_ok());
+}
+
+#[test]
+fn rejects_other_signers_messages_and_programs() {
+    let (presale, signer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let voucher = voucher(Pubkey::new_unique());
+    let message = voucher.message(&presale);
+    let instruction = ed25519_instruction(&signer, &message, CURRENT_INSTRUCTION);
+
+    assert!(verify_ed25519_instruction(&instruction, &Pubkey::new_unique(), &message).is_err());
+    // Voucher for another presale, or with a larger amount
+    assert!(verify_ed25519_instruction(&instruction, &signer, &voucher.message(&Pubkey::new_unique())).is_err());
+    let larger = PurchaseVoucher { max_amount: 2_000, ..voucher };
+    assert!(verify_ed25519_instruction(&instruction, &signer, &larger.message(&presale)).is_err());
+
+    let not_precompile = Instruction { program_id: Pubkey::new_unique(), ..instruction };
+    assert!(verify_ed25519_instruction(&not_precompile, &signer, &message).is_err());
+}
+
+#[test]
+fn rejects_data_read_from_other_instructions() {
+    let (presale, signer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let message = voucher(Pubkey::new_unique()).message(&presale);
+    let instruction = ed25519_instruction(&signer, &message, 0);
+    assert!(verify_ed25519_instruction(&instruction, &signer, &message).is_err());
+
+    let truncated = Instruction { data: vec![1, 0, 0], ..instruction };
+    assert!(verify_ed25519_instruction(&truncated, &signer, &message).is_err());
+}
+
+#[test]
+fn voucher_is_bound_to_buyer_amount_expiry_and_nonce() {
+    let buyer = Pubkey::new_unique();
+    let voucher = voucher(buyer);
+
+    assert!(voucher.check(&buyer, 1_000, NOW, 4).is_ok());
+    assert!(voucher.check(&Pubkey::new_unique(), 1_000, NOW, 4).is_err());
+    assert!(voucher.check(&buyer, 1_001, NOW, 4).is_err());
+    assert!(voucher.check(&buyer, 1_000, NOW + 301, 4).is_err());
+    // Replaying the same nonce, or an older one
+    assert!(voucher.check(&buyer, 1_000, NOW, 5).is_err());
+    assert!(voucher.check(&buyer, 1_000, NOW, 9).is_err());
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::TOKEN;
+use constants::state::{PresaleInfo, PresaleState, UserInfo};
+const SOFTCAP: u64 = 10_000_000_000;
+const END_TIME: i64 = 1_000;
+
+fn presale(state: PresaleState, total_raised: u64) -> PresaleInfo {
+    let mut presale = common::presale(&[(100 * TOKEN, 1_000)]);
+    presale.phases[0].end_time = Some(END_TIME);
+    presale.state = state;
+    presale.softcap_lamports = SOFTCAP;
+    presale.total_raised = total_raised;
+    presale
+}
+
+#[test]
+fn refund_opens_once_sales_end_below_the_softcap() {
+    for state in [PresaleState::Live, PresaleState::Paused] {
+        let below = presale(state, SOFTCAP - 1);
+        assert!(!below.is_refund_available(END_TIME - 1));
+        assert!(below.is_refund_available(END_TIME));
+
+        let reached = presale(state, SOFTCAP);
+        assert!(!reached.is_refund_available(END_TIME));
+    }
+}
+
+#[test]
+fn sold_out_presale_below_the_softcap_is_refundable() {
+    let mut presale = presale(PresaleState::Live, SOFTCAP - 1);
+    presale.remaining_tokens = 0;
+    assert!(presale.is_refund_available(0));
+}
+
+#[test]
+fn cancelled_presale_is_always_refundable() {
+    assert!(presale(PresaleState::Cancelled, SOFTCAP).is_refund_available(0));
+    assert!(presale(PresaleState::Cancelled, 0).is_refund_available(0));
+}
+
+#[test]
+fn draft_and_finalized_presales_are_not_refundable() {
+    assert!(!presale(PresaleState::Draft, 0).is_refund_available(END_TIME));
+    assert!(!presale(PresaleState::Finalized, SOFTCAP).is_refund_available(END_TIME));
+}
+
+#[test]
+fn second_refund_finds_nothing_to_refund() {
+    let mut presale = presale(PresaleState::Cancelled, 5_000);
+    presale.total_tokens_sold = 5 * TOKEN;
+    let mut user = UserInfo {
+        tokens_bought: 5 * TOKEN,
+        phase_purchases: vec![5 * TOKEN],
+        phase_claimed: vec![0],
+        total_paid: 5_000,
+        ..Default::default()
+    };
+
+    assert!(user.has_refundable_purchase());
+    let (tokens, lamports) = user.clear_for_refund();
+    presale.record_refund(tokens, lamports).unwrap();
+    assert_eq!((tokens, lamports), (5 * TOKEN, 5_000));
+    assert_eq!(presale.total_raised, 0);
+
+    assert!(!user.has_refundable_purchase());
+    assert_eq!(user.clear_for_refund(), (0, 0));
+}
+
+#[test]
+fn sol_stays_locked_below_the_softcap() {
+    let mut presale = presale(PresaleState::Live, SOFTCAP - 1);
+    assert!(!presale.is_sol_withdrawable());
+    assert!(presale.finalize(END_TIME).is_err());
+    assert_eq!(presale.state, PresaleState::Live);
+    assert!(!presale.is_sol_withdrawable());
+
+    presale.total_raised = SOFTCAP;
+    presale.finalize(END_TIME).unwrap();
+    assert!(presale.is_sol_withdrawable());
+    assert_eq!(presale.finalized_at, END_TIME);
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::TOKEN;
+use constants::state::{PhaseFill, PresaleInfo, UserInfo};
+// A 6 decimal stablecoin: 0.05 per token in phase 1, 0.10 in phase 2
+const USDC_PRICE_1: u64 = 50_000;
+const USDC_PRICE_2: u64 = 100_000;
+
+fn presale(mint: Pubkey, token_prices: &[Vec<u64>]) -> PresaleInfo {
+    let mut presale = common::presale(&vec![(100 * TOKEN, 1_000); token_prices.len()]);
+    for (phase, prices) in presale.phases.iter_mut().zip(token_prices) {
+        phase.token_prices = prices.clone();
+    }
+    presale.token_mint_address = Pubkey::new_unique();
+    presale.payment_mints = vec![mint];
+    presale.total_raised_tokens = vec![0];
+    presale
+}
+
+#[test]
+fn purchase_is_priced_in_the_payment_mint() {
+    let mint = Pubkey::new_unique();
+    let presale = presale(mint, &[vec![USDC_PRICE_1], vec![USDC_PRICE_2]]);
+    let index = presale.payment_mint_index(&mint).unwrap();
+
+    let fills = presale.plan_purchase_in(150 * TOKEN, 0, Some(index)).unwrap();
+    assert_eq!(fills, vec![
+        PhaseFill { phase_number: 1, amount: 100 * TOKEN, cost: 100 * USDC_PRICE_1 },
+        PhaseFill { phase_number: 2, amount: 50 * TOKEN, cost: 50 * USDC_PRICE_2 },
+    ]);
+
+    // The SOL price is unaffected
+    let fills = presale.plan_purchase(10 * TOKEN, 0).unwrap();
+    assert_eq!(fills[0].cost, 10_000);
+}
+
+#[test]
+fn phase_that_does_not_accept_the_mint_rejects_the_purchase() {
+    let mint = Pubkey::new_unique();
+    let presale = presale(mint, &[vec![USDC_PRICE_1], vec![0]]);
+
+    assert!(presale.plan_purchase_in(50 * TOKEN, 0, Some(0)).is_ok());
+    // Spilling into phase 2, which only sells for SOL
+    assert!(presale.plan_purchase_in(150 * TOKEN, 0, Some(0)).is_err());
+    assert!(presale.plan_purchase(150 * TOKEN, 0).is_ok());
+}
+
+#[test]
+fn unknown_mint_has_no_index() {
+    let presale = presale(Pubkey::new_unique(), &[vec![USDC_PRICE_1]]);
+    assert_eq!(presale.payment_mint_index(&Pubkey::new_unique()), None);
+    assert!(presale.plan_purchase_in(TOKEN, 0, Some(1)).is_err());
+}
+
+#[test]
+fn payment_mint_configuration_is_validated() {
+    let mint = Pubkey::new_unique();
+    let mut presale = presale(mint, &[vec![USDC_PRICE_1], vec![USDC_PRICE_2]]);
+    assert!(presale.is_valid_payment_mints());
+    assert!(presale.validate_phase_allocation());
+
+    // Every phase needs one price per payment mint
+    presale.phases[1].token_prices.clear();
+    assert!(!presale.validate_phase_allocation());
+
+    presale.payment_mints = vec![mint, mint];
+    assert!(!presale.is_valid_payment_mints());
+
+    presale.payment_mints = vec![presale.token_mint_address];
+    assert!(!presale.is_valid_payment_mints());
+}
+
+#[test]
+fn softcap_is_only_allowed_without_payment_mints() {
+    let mut presale = presale(Pubkey::new_unique(), &[vec![USDC_PRICE_1]]);
+    assert!(presale.is_valid_softcap());
+
+    presale.softcap_lamports = 1_000_000_000;
+    assert!(!presale.is_valid_softcap());
+
+    presale.payment_mints.clear();
+    assert!(presale.is_valid_softcap());
+}
+
+#[test]
+fn token_payments_are_tracked_and_refunded_per_mint() {
+    let mint = Pubkey::new_unique();
+    let mut presale = presale(mint, &[vec![USDC_PRICE_1]]);
+    let mut user = UserInfo {
+        phase_purchases: vec![10 * TOKEN],
+        phase_claimed: vec![0],
+        tokens_bought: 10 * TOKEN,
+        total_paid: 5_000,
+        token_paid: vec![0],
+        ..Default::default()
+    };
+
+    presale.record_token_payment(0, 500_000).unwrap();
+    user.record_token_payment(0, 500_000).unwrap();
+    assert_eq!(presale.total_raised_tokens, vec![500_000]);
+    assert!(user.record_token_payment(1, 1).is_err());
+
+    assert_eq!(user.take_token_refund(0), 500_000);
+    assert_eq!(user.take_token_refund(0), 0);
+    presale.record_token_refund(0, 500_000).unwrap();
+    assert_eq!(presale.total_raised_tokens, vec![0]);
+
+    // Refunding the token payment leaves the SOL payment for `refund`
+    assert_eq!(user.clear_allocation(), 10 * TOKEN);
+    assert_eq!(user.clear_for_refund(), (0, 5_000));
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::TOKEN;
+use constants::state::{PresaleInfo, PresaleState};
+
+fn presale(state: PresaleState) -> PresaleInfo {
+    PresaleInfo {
+        state,
+        total_tokens_deposited: 1_200 * TOKEN,
+        total_tokens_sold: 600 * TOKEN,
+        total_tokens_claimed: 100 * TOKEN,
+        remaining_tokens: 300 * TOKEN,
+        unsold_tokens: 100 * TOKEN,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn tokens_still_for_sale_are_owed_while_sales_can_resume() {
+    for state in [PresaleState::Draft, PresaleState::Live, PresaleState::Paused] {
+        let presale = presale(state);
+        // 500 unclaimed + 100 pending release + 300 for sale
+        assert_eq!(presale.outstanding_token_obligations(), 900 * TOKEN);
+        assert_eq!(presale.withdrawable_surplus(1_100 * TOKEN), 200 * TOKEN);
+    }
+}
+
+#[test]
+fn only_unclaimed_and_released_tokens_are_owed_once_sales_end() {
+    for state in [PresaleState::Finalized, PresaleState::Cancelled] {
+        let presale = presale(state);
+        assert_eq!(presale.outstanding_token_obligations(), 600 * TOKEN);
+        assert_eq!(presale.withdrawable_surplus(1_100 * TOKEN), 500 * TOKEN);
+    }
+}
+
+#[test]
+fn nothing_is_withdrawable_below_obligations() {
+    let presale = presale(PresaleState::Finalized);
+    assert_eq!(presale.withdrawable_surplus(600 * TOKEN), 0);
+    assert_eq!(presale.withdrawable_surplus(0), 0);
+}
+
+#[test]
+fn claims_reduce_obligations() {
+    let mut presale = presale(PresaleState::Finalized);
+    presale.record_claimed(500 * TOKEN).unwrap();
+    assert_eq!(presale.outstanding_token_obligations(), 100 * TOKEN);
+}
+
+fn live_presale(deposited: u64) -> PresaleInfo {
+    let mut presale = common::presale(&[(1_000 * TOKEN, 1_000)]);
+    presale.state = PresaleState::Live;
+    presale.deposit_tokens(deposited).unwrap();
+    presale
+}
+
+fn sell_out(presale: &mut PresaleInfo) {
+    assert!(presale.is_deposit_sufficient(presale.remaining_tokens));
+    for fill in presale.plan_purchase(presale.remaining_tokens, 0).unwrap() {
+        presale.apply_fill(&fill).unwrap();
+    }
+    assert_eq!(presale.remaining_tokens, 0);
+}
+
+#[test]
+fn withdrawing_tokens_sent_directly_keeps_the_deposit() {
+    let mut presale = live_presale(1_000 * TOKEN);
+    // 200 tokens transferred to the presale account outside `deposit_token`
+    let vault_balance = 1_200 * TOKEN;
+
+    assert!(presale.withdraw_surplus(200 * TOKEN + 1, vault_balance).is_err());
+    presale.withdraw_surplus(200 * TOKEN, vault_balance).unwrap();
+    assert_eq!(presale.total_tokens_deposited, 1_000 * TOKEN);
+    sell_out(&mut presale);
+}
+
+#[test]
+fn withdrawing_an_over_deposit_lowers_the_deposit() {
+    let mut presale = live_presale(1_200 * TOKEN);
+    presale.withdraw_surplus(200 * TOKEN, 1_200 * TOKEN).unwrap();
+    assert_eq!(presale.total_tokens_deposited, 1_000 * TOKEN);
+    sell_out(&mut presale);
+}
+
+#[test]
+fn mixed_surplus_takes_direct_transfers_first() {
+    let mut presale = live_presale(1_100 * TOKEN);
+    // 100 deposited above the sale plus 50 sent directly
+    presale.withdraw_surplus(120 * TOKEN, 1_150 * TOKEN).unwrap();
+    assert_eq!(presale.total_tokens_deposited, 1_030 * TOKEN);
+    sell_out(&mut presale);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use anchor_lang::error::Error;
+use common::{presale, TOKEN};
+use constants::errors::PresaleError;
+use constants::state::{PhaseStatus, PresaleInfo, PresaleState, UserInfo, VestingSchedule};
+const TGE: i64 = 1_000;
+const DAY: i64 = 86_400;
+
+// Phase 1 unlocks at TGE, phase 2 vests linearly over 100 days, phase 3 is not bought
+fn finalized_presale() -> PresaleInfo {
+    let vesting = [
+        VestingSchedule::default(),
+        VestingSchedule { tge_unlock_bps: 1_000, cliff_duration: 0, vesting_duration: 100 * DAY },
+        VestingSchedule::default(),
+    ];
+    let mut presale = presale(&[(1_000 * TOKEN, 1_000); 3]);
+    for (phase, vesting) in presale.phases.iter_mut().zip(vesting) {
+        phase.status = PhaseStatus::Ended;
+        phase.vesting = vesting;
+    }
+    presale.state = PresaleState::Finalized;
+    presale.claim_start_time = TGE;
+    presale.finalized_at = TGE;
+    presale.current_phase = 3;
+    presale
+}
+
+fn buyer() -> UserInfo {
+    UserInfo {
+        tokens_bought: 300 * TOKEN,
+        phase_purchases: vec![100 * TOKEN, 200 * TOKEN, 0],
+        phase_claimed: vec![0; 3],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn claim_all_matches_the_sum_of_per_phase_claims() {
+    let presale = finalized_presale();
+    let user = buyer();
+
+    for now in [TGE, TGE + 10 * DAY, TGE + 50 * DAY, TGE + 200 * DAY] {
+        let per_phase: u64 = (0..3).map(|i| presale.claimable_amount(&user, i, now)).sum();
+        let (claims, total) = presale.plan_claim_all(&user, now).unwrap();
+        assert_eq!(total, per_phase);
+        assert_eq!(claims.iter().map(|(_, amount)| amount).sum::<u64>(), total);
+        assert!(claims.iter().all(|(phase_number, _)| *phase_number != 3));
+    }
+}
+
+#[test]
+fn second_claim_all_with_nothing_vested_is_rejected() {
+    let presale = finalized_presale();
+    let mut user = buyer();
+    let now = TGE + 10 * DAY;
+
+    let (claims, total) = presale.plan_claim_all(&user, now).unwrap();
+    // Phase 1 in full, phase 2's 10% at TGE plus 10 of 100 days of the rest
+    assert_eq!(total, 100 * TOKEN + 20 * TOKEN + 18 * TOKEN);
+    for (phase_number, amount) in claims {
+        user.record_claim(phase_number, amount).unwrap();
+    }
+
+    let err = presale.plan_claim_all(&user, now).unwrap_err();
+    assert_eq!(err, Error::from(PresaleError::NothingVested));
+    // Later vesting unlocks more
+    assert_eq!(presale.plan_claim_all(&user, now + DAY).unwrap().1, 18 * TOKEN / 10);
+}
+
+#[test]
+fn claims_open_at_the_claim_start_time() {
+    let presale = finalized_presale();
+    let user = buyer();
+
+    assert!(!presale.is_claim_open(TGE - 1));
+    assert_eq!(presale.claimable_amount(&user, 0, TGE - 1), 0);
+    assert!(presale.plan_claim_all(&user, TGE - 1).is_err());
+
+    assert!(presale.is_claim_open(TGE));
+    assert_eq!(presale.claimable_amount(&user, 0, TGE), 100 * TOKEN);
+}
+
+#[test]
+fn claims_stay_closed_until_finalized() {
+    for state in [PresaleState::Draft, PresaleState::Live, PresaleState::Paused, PresaleState::Cancelled] {
+        let presale = PresaleInfo { state, ..finalized_presale() };
+        assert!(!presale.is_claim_open(TGE + DAY));
+    }
+}
+
+#[test]
+fn claim_start_time_is_fixed_once_the_presale_ends() {
+    for state in [PresaleState::Draft, PresaleState::Live, PresaleState::Paused] {
+        let mut presale = PresaleInfo { state, ..finalized_presale() };
+        presale.set_claim_start_time(TGE + DAY).unwrap();
+        assert_eq!(presale.claim_start_time, TGE + DAY);
+        assert!(presale.set_claim_start_time(-1).is_err());
+    }
+    for state in [PresaleState::Finalized, PresaleState::Cancelled] {
+        let mut presale = PresaleInfo { state, ..finalized_presale() };
+        assert!(presale.set_claim_start_time(TGE + DAY).is_err());
+        assert_eq!(presale.claim_start_time, TGE);
+    }
+}
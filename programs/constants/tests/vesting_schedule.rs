@@ -0,0 +1,52 @@
+use constants::state::VestingSchedule;
+
+const TOTAL: u64 = 1_000_000;
+const DAY: i64 = 86_400;
+
+#[test]
+fn default_schedule_unlocks_everything_at_tge() {
+    let vesting = VestingSchedule::default();
+    assert_eq!(vesting.vested_amount(TOTAL, -1), 0);
+    assert_eq!(vesting.vested_amount(TOTAL, 0), TOTAL);
+}
+
+#[test]
+fn tge_share_then_cliff_then_linear_release() {
+    let vesting = VestingSchedule {
+        tge_unlock_bps: 2_000,
+        cliff_duration: 30 * DAY,
+        vesting_duration: 100 * DAY,
+    };
+
+    assert_eq!(vesting.vested_amount(TOTAL, 0), 200_000);
+    assert_eq!(vesting.vested_amount(TOTAL, 30 * DAY - 1), 200_000);
+    assert_eq!(vesting.vested_amount(TOTAL, 30 * DAY), 200_000);
+    assert_eq!(vesting.vested_amount(TOTAL, 80 * DAY), 600_000);
+    assert_eq!(vesting.vested_amount(TOTAL, 130 * DAY), TOTAL);
+    assert_eq!(vesting.vested_amount(TOTAL, 1_000 * DAY), TOTAL);
+}
+
+#[test]
+fn vested_amount_never_decreases() {
+    let vesting = VestingSchedule {
+        tge_unlock_bps: 1_234,
+        cliff_duration: 7,
+        vesting_duration: 101,
+    };
+    let mut previous = 0;
+    for elapsed in 0..200 {
+        let vested = vesting.vested_amount(TOTAL, elapsed);
+        assert!(vested >= previous);
+        assert!(vested <= TOTAL);
+        previous = vested;
+    }
+    assert_eq!(previous, TOTAL);
+}
+
+#[test]
+fn invalid_schedules_are_rejected() {
+    assert!(!VestingSchedule { tge_unlock_bps: 10_001, ..Default::default() }.is_valid());
+    assert!(!VestingSchedule { cliff_duration: -1, ..Default::default() }.is_valid());
+    assert!(!VestingSchedule { vesting_duration: -1, ..Default::default() }.is_valid());
+    assert!(VestingSchedule { tge_unlock_bps: 10_000, ..Default::default() }.is_valid());
+}
@@ -21,6 +21,21 @@ export const TOKEN_MINT = new anchor.web3.PublicKey(
   "Ah1hf7NZgBhgnhFsrXLoj7czMMiVwUaCHnc5bP9wB6Ge"
 );
 
+// Optional SPL payment mint (e.g. a USDC-like stablecoin) accepted besides SOL,
+// create a mock one for local testing with `integration/createMockPaymentMint.ts`
+export const PAYMENT_MINT = process.env.PAYMENT_MINT
+  ? new anchor.web3.PublicKey(process.env.PAYMENT_MINT)
+  : null;
+
+// Optional SOL/USD price account (Pyth-style layout) to price phases in USD,
+// write a mock one for a local validator with `integration/writeMockPriceFeed.ts`
+export const PRICE_FEED = process.env.PRICE_FEED
+  ? new anchor.web3.PublicKey(process.env.PRICE_FEED)
+  : null;
+
+// Presale to operate on, one authority can run several (seed, private, public...)
+export const PRESALE_ID = new anchor.BN(process.env.PRESALE_ID ?? 0);
+
 // Load authority keypair from file
 const adminKeypair = JSON.parse(
   fs.readFileSync(path.join(__dirname, "../../keypair.json"), "utf-8")
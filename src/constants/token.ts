@@ -37,3 +37,45 @@ export const PHASE_PRICES = {
   PHASE_5: new BN(15_000), // 0.000015 SOL (0.75 SOL total)
   PHASE_6: new BN(20_000), // 0.00002 SOL (1 SOL total)
 };
+
+// Prices in a 6 decimal stablecoin, base units per whole token
+export const PAYMENT_MINT_DECIMALS = 6;
+export const PHASE_STABLECOIN_PRICES = {
+  PHASE_1: new BN(5_000), // 0.005 (250 total)
+  PHASE_2: new BN(3_000), // 0.003 (300 total)
+  PHASE_3: new BN(1_000), // 0.001 (350 total)
+  PHASE_4: new BN(1_100), // 0.0011 (440 total)
+  PHASE_5: new BN(2_000), // 0.002 (100 total)
+  PHASE_6: new BN(2_500), // 0.0025 (125 total)
+};
+
+// Default phase schedule passed to `create_presale`
+const phaseConfig = (
+  amount: BN,
+  price: BN,
+  stablecoinPrice: BN,
+  minTokens: number
+) => ({
+  amount,
+  price,
+  minPurchase: new BN(minTokens).mul(DECIMALS_MULTIPLIER),
+  maxPurchase: amount,
+  maxPerWallet: null, // no per-phase cap, only the lifetime MAX_PER_ADDRESS
+  startTime: null, // phases open as soon as the previous one sells out
+  endTime: null,
+  // everything unlocks at the TGE, no cliff or linear release
+  vesting: { tgeUnlockBps: 10_000, cliffDuration: new BN(0), vestingDuration: new BN(0) },
+  // one price per payment mint, left out when the presale only accepts SOL
+  tokenPrices: [stablecoinPrice],
+  // Merkle root from `cargo run --example allowlist`, null opens the phase to everyone
+  allowlistRoot: null,
+});
+
+export const PHASE_CONFIGS = [
+  phaseConfig(PHASE_1, PHASE_PRICES.PHASE_1, PHASE_STABLECOIN_PRICES.PHASE_1, 100),
+  phaseConfig(PHASE_2, PHASE_PRICES.PHASE_2, PHASE_STABLECOIN_PRICES.PHASE_2, 200),
+  phaseConfig(PHASE_3, PHASE_PRICES.PHASE_3, PHASE_STABLECOIN_PRICES.PHASE_3, 300),
+  phaseConfig(PHASE_4, PHASE_PRICES.PHASE_4, PHASE_STABLECOIN_PRICES.PHASE_4, 400),
+  phaseConfig(PHASE_5, PHASE_PRICES.PHASE_5, PHASE_STABLECOIN_PRICES.PHASE_5, 500),
+  phaseConfig(PHASE_6, PHASE_PRICES.PHASE_6, PHASE_STABLECOIN_PRICES.PHASE_6, 600),
+];
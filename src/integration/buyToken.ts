@@ -5,13 +5,16 @@ import {
   createAssociatedTokenAccountInstruction,
 } from "@solana/spl-token";
 import {
+  PublicKey,
   SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
   SYSVAR_RENT_PUBKEY,
+  TransactionInstruction,
   Transaction,
 } from "@solana/web3.js";
 import * as anchor from "@coral-xyz/anchor";
 import { program, connection } from "../config/integrationConnection";
-import { buyerKeypair, TOKEN_MINT } from "../constants";
+import { buyerKeypair, PRICE_FEED, TOKEN_MINT } from "../constants";
 import { TOKEN_AMOUNTS } from "../constants/token";
 import { formatTokenAmount } from "../utils/format";
 import {
@@ -19,13 +22,25 @@ import {
   derivePresaleVaultAddress,
   deriveUserInfoAddress,
 } from "../utils/pda";
+import { PurchaseVoucher } from "../utils/voucher";
 
-export const buyToken = async (amount: anchor.BN) => {
+export const buyToken = async (
+  amount: anchor.BN,
+  maxPayment: anchor.BN,
+  beneficiary: PublicKey = buyerKeypair.publicKey,
+  // `{ maxAllocation, proof }` of the beneficiary from the allowlist example output,
+  // only needed when buying from an allowlisted phase
+  allowlistProof: { maxAllocation: anchor.BN; proof: number[][] } | null = null,
+  // KYC voucher and its Ed25519 instruction from `signVoucher`, only needed
+  // when the presale has a KYC signer
+  kyc: { voucher: PurchaseVoucher; signatureIx: TransactionInstruction } | null = null
+) => {
   try {
     // Derive necessary addresses
     const { presaleAddress, bump } = await derivePresaleAddress();
     const { presaleVault } = await derivePresaleVaultAddress();
-    const userInfoAddress = await deriveUserInfoAddress();
+    // The allocation is recorded against the beneficiary, the buyer only pays
+    const userInfoAddress = await deriveUserInfoAddress(beneficiary);
 
     // Get or create buyer's token account
     const buyerTokenAccount = await getAssociatedTokenAddressSync(
@@ -66,7 +81,9 @@ export const buyToken = async (amount: anchor.BN) => {
     console.log({
       amount: formatTokenAmount(amount),
       rawAmount: amount.toString(),
+      maxPayment: `${maxPayment.toString()} lamports`,
       buyer: buyerKeypair.publicKey.toString(),
+      beneficiary: beneficiary.toString(),
       userInfo: userInfoAddress.toString(),
       presaleAddress: presaleAddress.toString(),
       presaleVault: presaleVault.toString(),
@@ -76,7 +93,7 @@ export const buyToken = async (amount: anchor.BN) => {
 
     // Execute the buy token transaction
     const tx = await program.methods
-      .buyToken(amount)
+      .buyToken(beneficiary, amount, maxPayment, allowlistProof, kyc?.voucher ?? null)
       .accounts({
         // @ts-ignore
         presaleInfo: presaleAddress,
@@ -85,11 +102,15 @@ export const buyToken = async (amount: anchor.BN) => {
         buyer: buyerKeypair.publicKey,
         buyerTokenAccount,
         presaleTokenAccount,
+        priceFeed: PRICE_FEED, // only needed for USD priced presales
+        instructionsSysvar: kyc ? SYSVAR_INSTRUCTIONS_PUBKEY : null,
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
+      // the voucher signature must be verified right before the purchase
+      .preInstructions(kyc ? [kyc.signatureIx] : [])
       .signers([buyerKeypair])
       .rpc();
 
@@ -140,7 +161,10 @@ if (require.main === module) {
     "tokens..."
   );
 
-  buyToken(purchaseAmount)
+  // Allow paying up to 0.01 SOL in case the phase price moves before landing
+  const maxPayment = new anchor.BN(10_000_000);
+
+  buyToken(purchaseAmount, maxPayment)
     .then((result) => {
       console.log("Purchase completed successfully!");
     })
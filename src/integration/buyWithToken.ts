@@ -0,0 +1,92 @@
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import * as anchor from "@coral-xyz/anchor";
+import { program } from "../config/integrationConnection";
+import { buyerKeypair, PAYMENT_MINT } from "../constants";
+import { formatTokenAmount } from "../utils/format";
+import { derivePresaleAddress, deriveUserInfoAddress } from "../utils/pda";
+import { PurchaseVoucher } from "../utils/voucher";
+
+// Buys `amount` presale tokens paying in the configured SPL payment mint,
+// spending at most `maxPayment` base units of it
+export const buyWithToken = async (
+  amount: anchor.BN,
+  maxPayment: anchor.BN,
+  beneficiary: PublicKey = buyerKeypair.publicKey,
+  allowlistProof: { maxAllocation: anchor.BN; proof: number[][] } | null = null,
+  kyc: { voucher: PurchaseVoucher; signatureIx: TransactionInstruction } | null = null
+) => {
+  if (!PAYMENT_MINT) {
+    throw new Error("Missing PAYMENT_MINT in .env file");
+  }
+
+  try {
+    const { presaleAddress } = await derivePresaleAddress();
+    const userInfoAddress = await deriveUserInfoAddress(beneficiary);
+
+    const buyerPaymentAccount = getAssociatedTokenAddressSync(
+      PAYMENT_MINT,
+      buyerKeypair.publicKey
+    );
+    const presalePaymentAccount = getAssociatedTokenAddressSync(
+      PAYMENT_MINT,
+      presaleAddress,
+      true // allow owner off curve
+    );
+
+    console.log("Buying tokens with following details:");
+    console.log({
+      amount: formatTokenAmount(amount),
+      maxPayment: maxPayment.toString(),
+      paymentMint: PAYMENT_MINT.toString(),
+      buyer: buyerKeypair.publicKey.toString(),
+      beneficiary: beneficiary.toString(),
+    });
+
+    const tx = await program.methods
+      .buyWithToken(beneficiary, amount, maxPayment, allowlistProof, kyc?.voucher ?? null)
+      .accounts({
+        // @ts-ignore
+        presaleInfo: presaleAddress,
+        userInfo: userInfoAddress,
+        paymentMint: PAYMENT_MINT,
+        buyerPaymentAccount,
+        presalePaymentAccount,
+        buyer: buyerKeypair.publicKey,
+        instructionsSysvar: kyc ? SYSVAR_INSTRUCTIONS_PUBKEY : null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .preInstructions(kyc ? [kyc.signatureIx] : [])
+      .signers([buyerKeypair])
+      .rpc();
+
+    console.log("Transaction successful! Signature:", tx);
+    return tx;
+  } catch (error) {
+    console.error("Error buying tokens with payment mint:", error);
+    throw error;
+  }
+};
+
+// Execute if running directly
+if (require.main === module) {
+  // Buy 1,000 tokens, paying up to 10 units of the 6 decimal payment mint
+  const purchaseAmount = new anchor.BN(1_000).mul(new anchor.BN(10 ** 9));
+  const maxPayment = new anchor.BN(10_000_000);
+
+  buyWithToken(purchaseAmount, maxPayment).catch((error) => {
+    console.error("Purchase failed:", error);
+    process.exit(1);
+  });
+}
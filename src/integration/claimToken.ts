@@ -15,9 +15,11 @@ export const claimToken = async () => {
     const userInfoAddress = await deriveUserInfoAddress();
 
     // Get token accounts
+    // Tokens can be delivered to any wallet, the buyer's own by default
+    const destinationOwner = buyerKeypair.publicKey;
     const tokenAccount = await getAssociatedTokenAddressSync(
       TOKEN_MINT,
-      buyerKeypair.publicKey
+      destinationOwner
     );
 
     const presaleTokenAccount = await getAssociatedTokenAddressSync(
@@ -32,6 +34,7 @@ export const claimToken = async () => {
         tokenMint: TOKEN_MINT, // Token mint
         // @ts-ignore
         tokenAccount, // Token account
+        destinationOwner, // Owner of the receiving token account
         presaleAssociatedTokenAccount: presaleTokenAccount, // Presale token account
         userInfo: userInfoAddress, // User info PDA
         presaleInfo: presaleAddress, // Presale info PDA
@@ -0,0 +1,46 @@
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { connection } from "../config/integrationConnection";
+import { authorityKeypair, buyerKeypair } from "../constants";
+import { PAYMENT_MINT_DECIMALS } from "../constants/token";
+
+// Creates a USDC-like mock payment mint and funds the buyer with it, for
+// testing `buy_with_token` locally or on devnet. Set the printed address as
+// PAYMENT_MINT before running createPresale.
+export const createMockPaymentMint = async (wholeTokens = 10_000) => {
+  const mint = await createMint(
+    connection,
+    authorityKeypair, // Payer
+    authorityKeypair.publicKey, // Mint authority
+    null, // No freeze authority
+    PAYMENT_MINT_DECIMALS
+  );
+  console.log("Mock payment mint:", mint.toBase58());
+
+  const buyerAccount = await getOrCreateAssociatedTokenAccount(
+    connection,
+    authorityKeypair,
+    mint,
+    buyerKeypair.publicKey
+  );
+  await mintTo(
+    connection,
+    authorityKeypair,
+    mint,
+    buyerAccount.address,
+    authorityKeypair,
+    BigInt(wholeTokens) * BigInt(10 ** PAYMENT_MINT_DECIMALS)
+  );
+  console.log(`Minted ${wholeTokens} mock tokens to the buyer`);
+  console.log(`Add PAYMENT_MINT=${mint.toBase58()} to your .env file`);
+
+  return mint;
+};
+
+// Execute if running directly
+if (require.main === module) {
+  createMockPaymentMint().catch(console.error);
+}
@@ -1,31 +1,73 @@
 import { SystemProgram } from "@solana/web3.js";
 import * as anchor from "@coral-xyz/anchor";
-import { derivePresaleAddress } from "../utils/pda";
+import { derivePresaleAddress, derivePresaleVaultAddress } from "../utils/pda";
 import { program } from "../config/integrationConnection";
-import { authorityKeypair, TOKEN_MINT } from "../constants";
-import { TOKEN_AMOUNTS } from "../constants/token";
+import {
+  authorityKeypair,
+  PAYMENT_MINT,
+  PRESALE_ID,
+  PRICE_FEED,
+  TOKEN_MINT,
+} from "../constants";
+import { PHASE_CONFIGS, TOKEN_AMOUNTS } from "../constants/token";
 import { formatTokenAmount } from "../utils/format";
 
 export const createPresale = async () => {
   const { presaleAddress } = await derivePresaleAddress();
+  const { presaleVault } = await derivePresaleVaultAddress();
+
+  // Stablecoin prices only apply when a payment mint is configured
+  const paymentMints = PAYMENT_MINT ? [PAYMENT_MINT] : [];
+  const phaseConfigs = PHASE_CONFIGS.map((phase) => ({
+    ...phase,
+    tokenPrices: PAYMENT_MINT ? phase.tokenPrices : [],
+  }));
 
   // const startTime = new anchor.BN(Math.floor(Date.now() / 1000));
-  // const endTime = new anchor.BN(Math.floor(Date.now() / 1000) + 86400 * 5); // 5 days
+  const displayEndTime = new anchor.BN(Math.floor(Date.now() / 1000) + 86400 * 5); // 5 days
 
   try {
     console.log("Creating presale with following parameters:");
     console.log({
+      presaleId: PRESALE_ID.toString(),
       tokenMint: TOKEN_MINT.toString(),
       // startTime: new Date(startTime.toNumber() * 1000).toISOString(),
       // endTime: new Date(endTime.toNumber() * 1000).toISOString(),
       maxTokensPerAddress: formatTokenAmount(TOKEN_AMOUNTS.MAX_PER_ADDRESS),
+      phases: PHASE_CONFIGS.map((phase) => ({
+        amount: formatTokenAmount(phase.amount),
+        price: `${phase.price.toString()} lamports`,
+      })),
+      paymentMints: paymentMints.map((mint) => mint.toString()),
     });
 
     const tx = await program.methods
-      .createPresale(TOKEN_MINT, TOKEN_AMOUNTS.MAX_PER_ADDRESS)
+      .createPresale({
+        presaleId: PRESALE_ID,
+        tokenMintAddress: TOKEN_MINT,
+        maxTokenAmountPerAddress: TOKEN_AMOUNTS.MAX_PER_ADDRESS,
+        displayEndTime,
+        phaseConfigs,
+        // unsold tokens of a phase closed by time move to the next phase
+        unsoldTokenPolicy: { rollOver: {} },
+        // softcap in lamports, 0 disables refunds (must be 0 with payment mints)
+        softcapLamports: new anchor.BN(0),
+        paymentMints,
+        // with a price feed, phase prices are read as USD micro-units per token
+        priceFeed: PRICE_FEED
+          ? {
+              feed: PRICE_FEED,
+              maxAge: new anchor.BN(60), // seconds
+              maxConfidenceBps: 100, // 1% of the price
+            }
+          : null,
+        // compliance key signing purchase vouchers, null disables KYC
+        kycSigner: null,
+      })
       .accounts({
         // @ts-ignore
         presaleInfo: presaleAddress,
+        presaleVault,
         authority: authorityKeypair.publicKey,
         systemProgram: SystemProgram.programId,
       })
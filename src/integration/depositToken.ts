@@ -9,14 +9,7 @@ import { program, connection } from "../config/integrationConnection";
 import { authorityKeypair, TOKEN_MINT } from "../constants";
 import { TOKEN_AMOUNTS } from "../constants/token";
 import { formatTokenAmount } from "../utils/format";
-import { derivePresaleAddress, derivePresaleVaultAddress } from "../utils/pda";
-
-// Helper function to convert SOL to lamports with proper BN handling
-const solToLamports = (amount: number): anchor.BN => {
-  // Convert to string first to handle large numbers safely
-  const lamports = (amount * Math.pow(10, 9)).toString();
-  return new anchor.BN(lamports);
-};
+import { derivePresaleAddress } from "../utils/pda";
 
 export const depositToken = async () => {
   try {
@@ -24,7 +17,6 @@ export const depositToken = async () => {
     const depositAmount = TOKEN_AMOUNTS.TOTAL_SUPPLY;
 
     const { presaleAddress } = await derivePresaleAddress();
-    const { presaleVault } = await derivePresaleVaultAddress();
 
     // Get token accounts
     const adminTokenAccount = await getAssociatedTokenAddressSync(
@@ -59,7 +51,6 @@ export const depositToken = async () => {
       adminTokenAccount: adminTokenAccount.toString(),
       presaleTokenAccount: presaleTokenAccount.toString(),
       presaleAddress: presaleAddress.toString(),
-      presaleVault: presaleVault.toString(),
     });
 
     const tx = await program.methods
@@ -70,9 +61,7 @@ export const depositToken = async () => {
         tokenAccount: adminTokenAccount,
         admin: authorityKeypair.publicKey,
         toAssociatedTokenAccount: presaleTokenAccount,
-        presaleVault,
         presaleInfo: presaleAddress,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
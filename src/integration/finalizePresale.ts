@@ -0,0 +1,31 @@
+import { program } from "../config/integrationConnection";
+import { authorityKeypair } from "../constants";
+import { derivePresaleAddress } from "../utils/pda";
+
+// Closes sales and unlocks claims, the softcap must have been reached
+export const finalizePresale = async () => {
+  try {
+    const { presaleAddress } = await derivePresaleAddress();
+
+    const tx = await program.methods
+      .finalizePresale()
+      .accounts({
+        // @ts-ignore
+        presaleInfo: presaleAddress, // Presale info PDA
+        authority: authorityKeypair.publicKey, // Presale authority
+      })
+      .signers([authorityKeypair])
+      .rpc();
+
+    console.log("Presale finalized! Transaction signature:", tx);
+    return tx;
+  } catch (error) {
+    console.error("Error finalizing presale:", error);
+    throw error;
+  }
+};
+
+// Execute if running directly
+if (require.main === module) {
+  finalizePresale().catch(console.error);
+}
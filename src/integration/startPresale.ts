@@ -0,0 +1,31 @@
+import { program } from "../config/integrationConnection";
+import { authorityKeypair } from "../constants";
+import { derivePresaleAddress } from "../utils/pda";
+
+// Opens a draft presale for purchases, run once the tokens are deposited
+export const startPresale = async () => {
+  try {
+    const { presaleAddress } = await derivePresaleAddress();
+
+    const tx = await program.methods
+      .startPresale()
+      .accounts({
+        // @ts-ignore
+        presaleInfo: presaleAddress, // Presale info PDA
+        authority: authorityKeypair.publicKey, // Presale authority
+      })
+      .signers([authorityKeypair])
+      .rpc();
+
+    console.log("Presale started! Transaction signature:", tx);
+    return tx;
+  } catch (error) {
+    console.error("Error starting presale:", error);
+    throw error;
+  }
+};
+
+// Execute if running directly
+if (require.main === module) {
+  startPresale().catch(console.error);
+}
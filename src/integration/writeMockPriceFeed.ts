@@ -0,0 +1,56 @@
+import * as fs from "fs";
+import * as path from "path";
+import { Keypair, PublicKey } from "@solana/web3.js";
+
+// Pyth v2 price account layout, only the fields the presale reads
+const PRICE_ACCOUNT_SIZE = 240;
+const MAGIC = 0xa1b2c3d4;
+const PRICE_ACCOUNT_TYPE = 3;
+const STATUS_TRADING = 1;
+
+// Writes a mock SOL/USD price account to a JSON file that a local validator can
+// load: solana-test-validator --account <address> <file>. Set the printed
+// address as PRICE_FEED. The publish time is now, so restart the validator with
+// a fresh file once it goes stale.
+export const writeMockPriceFeed = (
+  usdPerSol = 150,
+  confidencePct = 0.1,
+  outFile = path.join(__dirname, "../../mock-price-feed.json")
+) => {
+  const expo = -8;
+  const price = BigInt(Math.round(usdPerSol * 10 ** -expo));
+  const conf = BigInt(Math.round((usdPerSol * confidencePct * 10 ** -expo) / 100));
+  const now = BigInt(Math.floor(Date.now() / 1000));
+
+  const data = Buffer.alloc(PRICE_ACCOUNT_SIZE);
+  data.writeUInt32LE(MAGIC, 0);
+  data.writeUInt32LE(PRICE_ACCOUNT_TYPE, 8);
+  data.writeInt32LE(expo, 20);
+  data.writeBigInt64LE(now, 96); // publish time
+  data.writeBigInt64LE(price, 208); // aggregate price
+  data.writeBigUInt64LE(conf, 216); // aggregate confidence
+  data.writeUInt32LE(STATUS_TRADING, 224);
+
+  const address = Keypair.generate().publicKey;
+  const account = {
+    pubkey: address.toBase58(),
+    account: {
+      lamports: 10_000_000,
+      data: [data.toString("base64"), "base64"],
+      owner: PublicKey.default.toBase58(),
+      executable: false,
+      rentEpoch: 0,
+    },
+  };
+  fs.writeFileSync(outFile, JSON.stringify(account, null, 2));
+
+  console.log(`Mock price feed: $${usdPerSol} per SOL written to ${outFile}`);
+  console.log(`solana-test-validator --account ${address.toBase58()} ${outFile}`);
+  console.log(`Add PRICE_FEED=${address.toBase58()} to your .env file`);
+  return address;
+};
+
+// Execute if running directly
+if (require.main === module) {
+  writeMockPriceFeed();
+}
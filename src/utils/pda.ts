@@ -1,24 +1,41 @@
+import { BN } from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
-import { PROGRAM_ID, authorityKeypair, buyerKeypair } from "../constants";
+import {
+  PROGRAM_ID,
+  PRESALE_ID,
+  authorityKeypair,
+  buyerKeypair,
+} from "../constants";
 
-export const derivePresaleAddress = async () => {
+export const derivePresaleAddress = async (
+  presaleId: BN = PRESALE_ID,
+  authority: PublicKey = authorityKeypair.publicKey
+) => {
   const [presaleAddress, bump] = PublicKey.findProgramAddressSync(
-    [Buffer.from("presale"), authorityKeypair.publicKey.toBuffer()],
+    [
+      Buffer.from("presale"),
+      authority.toBuffer(),
+      presaleId.toArrayLike(Buffer, "le", 8),
+    ],
     PROGRAM_ID
   );
   return { presaleAddress, bump };
 };
 
-export const derivePresaleVaultAddress = async () => {
+export const derivePresaleVaultAddress = async (presaleId: BN = PRESALE_ID) => {
+  const { presaleAddress } = await derivePresaleAddress(presaleId);
   const [presaleVault, bump] = PublicKey.findProgramAddressSync(
-    [Buffer.from("vault")],
+    [Buffer.from("vault"), presaleAddress.toBuffer()],
     PROGRAM_ID
   );
   return { presaleVault, bump };
 };
 
-export const deriveUserInfoAddress = async (buyer?: PublicKey) => {
-  const { presaleAddress } = await derivePresaleAddress();
+export const deriveUserInfoAddress = async (
+  buyer?: PublicKey,
+  presaleId: BN = PRESALE_ID
+) => {
+  const { presaleAddress } = await derivePresaleAddress(presaleId);
   const [userInfoAddress] = PublicKey.findProgramAddressSync(
     [
       Buffer.from("user"),
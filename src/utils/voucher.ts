@@ -0,0 +1,36 @@
+import { BN } from "@coral-xyz/anchor";
+import {
+  Ed25519Program,
+  Keypair,
+  PublicKey,
+  TransactionInstruction,
+} from "@solana/web3.js";
+
+export type PurchaseVoucher = {
+  buyer: PublicKey; // wallet receiving the allocation (the purchase beneficiary)
+  maxAmount: BN; // most tokens the purchase may buy, in base units
+  expiry: BN; // Unix timestamp
+  nonce: BN; // higher than the buyer's last used nonce
+};
+
+// Bytes the KYC signer signs: presale || buyer || max_amount || expiry || nonce
+export const voucherMessage = (presale: PublicKey, voucher: PurchaseVoucher) =>
+  Buffer.concat([
+    presale.toBuffer(),
+    voucher.buyer.toBuffer(),
+    voucher.maxAmount.toArrayLike(Buffer, "le", 8),
+    voucher.expiry.toTwos(64).toArrayLike(Buffer, "le", 8),
+    voucher.nonce.toArrayLike(Buffer, "le", 8),
+  ]);
+
+// Signs a voucher with the KYC signer, as the compliance backend would. The
+// returned instruction must be placed right before the purchase instruction.
+export const signVoucher = (
+  kycSigner: Keypair,
+  presale: PublicKey,
+  voucher: PurchaseVoucher
+): TransactionInstruction =>
+  Ed25519Program.createInstructionWithPrivateKey({
+    privateKey: kycSigner.secretKey,
+    message: voucherMessage(presale, voucher),
+  });
@@ -1,2 +1 @@
 // claiming token worng
-// deposit token work only once